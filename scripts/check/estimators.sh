@@ -0,0 +1,12 @@
+# Compare the analog and next-event detector tallies of a shifting medium.
+# Both estimators are unbiased, so their mean totals should agree within the standard errors.
+# The next-event totals are heavy-tailed, so occasional seeds land far above the rest.
+
+cd $DIA_DIR/input/mcrt/check;
+for estimator in Analog NextEvent; do
+    for seed in 1 2 3 4 5 6 7 8; do
+        sed -e "s/estimator: 'Analog'/estimator: '$estimator'/" -e "s/seed:               0/seed:               $seed/" shift.json5 > run.json5;
+        cargo run --bin mcrt --release check/run.json5 | grep "detector det total" | awk -v e=$estimator '{print e, $NF}';
+    done;
+done | awk '{n[$1]++; s[$1]+=$2; q[$1]+=$2*$2} END {for (e in n) {m=s[e]/n[e]; print e, "mean:", m, "standard error:", sqrt((q[e]/n[e]-m*m)/(n[e]-1))}}';
+rm run.json5;
@@ -0,0 +1,28 @@
+# Compare an interrupted-then-resumed run against an uninterrupted run of the same parameters.
+# Each thread restores its generator and tallies from its checkpoint, so the resumed totals should match exactly.
+
+cd $DIA_DIR/input/mcrt/check;
+sed -e "s/num_phot:           2e5/num_phot:           2e6/" -e "s/seed:               0,/seed:               0,\n        threads:            2,\n        checkpoint:         1e4,\n        batches:            10,/" shift.json5 > run.json5;
+cargo build --bin mcrt --release;
+check_dir=$DIA_DIR/output/mcrt/checkpoint;
+
+rm -rf $check_dir;
+cargo run --bin mcrt --release check/run.json5 | grep -E "total|error|batches" > uninterrupted.txt;
+
+# Interrupt the run once the first checkpoint has been written.
+rm -rf $check_dir;
+cargo run --bin mcrt --release check/run.json5 > /dev/null &
+pid=$!;
+while [ ! -e $check_dir/thread_0.json ]; do sleep 0.1; done;
+kill $pid;
+wait $pid;
+grep -o '"count":[0-9]*' $check_dir/*.json;
+
+cargo run --bin mcrt --release check/run.json5 --resume $check_dir | grep -E "total|error|batches" > resumed.txt;
+
+if diff uninterrupted.txt resumed.txt; then
+    echo "Resumed run matches the uninterrupted run.";
+else
+    echo "Resumed run differs from the uninterrupted run.";
+fi;
+rm run.json5 uninterrupted.txt resumed.txt;
@@ -24,3 +24,6 @@ cargo check;
 
 # Run the binary, in release (optimised) mode, with the parameters input file.
 cargo run --bin mcrt --release parameters.json5;
+
+# With a checkpoint interval set, an interrupted run can be continued from its last checkpoint.
+# cargo run --bin mcrt --release parameters.json5 --resume $DIA_DIR/output/mcrt/checkpoint;
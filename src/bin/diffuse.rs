@@ -0,0 +1,189 @@
+//! Diffusion approximation binary.
+
+use attr::input;
+use dia::*;
+use std::path::{Path, PathBuf};
+
+/// Input parameters.
+#[input]
+struct Parameters {
+    /// Adaptive mesh settings.
+    tree: tree::Settings,
+    /// Regular grid settings.
+    grid: grid::Settings,
+    /// Diffusion settings.
+    sett: diffuse::Settings,
+    /// Light sources map.
+    lights: Set<form::Light>,
+    /// Surfaces map.
+    surfs: Set<form::Mesh>,
+    /// Materials map.
+    mats: Set<Redirect<form::Material>>,
+    /// Material interfaces map.
+    inters: Set<mcrt::Interface>,
+}
+
+/// Main function.
+pub fn main() {
+    banner::title("Diffuse");
+    let (params_path, in_dir, out_dir) = init();
+    let params = input(&in_dir, &params_path);
+    let (tree_sett, grid_sett, diffuse_sett, lights, surfs, mats, inters) = build(&in_dir, params);
+    let (tree, grid) = grow(tree_sett, grid_sett, &surfs, &mats);
+    let data = render(&diffuse_sett, &lights, &mats, &inters, &tree, &grid);
+    report!("output data", &data);
+    save(&out_dir, data);
+    banner::section("Finished");
+}
+
+/// Initialise the command line arguments and directories.
+fn init() -> (PathBuf, PathBuf, PathBuf) {
+    banner::section("Initialisation");
+    banner::sub_section("Command line arguments");
+    args!(bin_path: PathBuf;
+        params_path: PathBuf
+    );
+    report!("binary path", bin_path.display());
+    report!("parameters path", params_path.display());
+
+    banner::sub_section("Directories");
+    let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
+    report!("input directory", in_dir.display());
+    report!("output directory", out_dir.display());
+
+    (params_path, in_dir, out_dir)
+}
+
+/// Load the input files.
+fn input(in_dir: &Path, params_path: &Path) -> Parameters {
+    banner::section("Input");
+    banner::sub_section("Parameters");
+    let path = in_dir.join(params_path);
+
+    Parameters::load(&path).expect("Could not load parameters file")
+}
+
+/// Build instances.
+#[allow(clippy::type_complexity)]
+fn build(
+    in_dir: &Path,
+    params: Parameters,
+) -> (
+    tree::Settings,
+    grid::Settings,
+    diffuse::Settings,
+    Set<mcrt::Light>,
+    Set<Mesh>,
+    Set<mcrt::Material>,
+    Set<mcrt::Interface>,
+) {
+    banner::section("Building");
+    banner::sub_section("Adaptive Tree Settings");
+    let tree_sett = params.tree;
+    report!("Tree settings", &tree_sett);
+
+    banner::sub_section("Grid Settings");
+    let grid_sett = params.grid;
+    report!("Grid settings", &grid_sett);
+
+    banner::sub_section("Diffusion Settings");
+    let diffuse_sett = params.sett;
+    report!("Diffusion settings", &diffuse_sett);
+
+    banner::sub_section("Lights");
+    let lights = params
+        .lights
+        .build(in_dir)
+        .expect("Unable to build lights.");
+    report!("Lights", &lights);
+
+    banner::sub_section("Surfaces");
+    let surfs = params
+        .surfs
+        .build(in_dir)
+        .expect("Unable to build surfaces.");
+    report!("Surfaces", &surfs);
+
+    banner::sub_section("Properties");
+    let mats = params
+        .mats
+        .build(in_dir)
+        .expect("Unable to build materials.")
+        .build(in_dir)
+        .expect("Unable to build materials.");
+    report!("Properties", &mats);
+
+    banner::sub_section("Interfaces");
+    let inters = params.inters;
+    mcrt::check_materials(&surfs, &inters, &mats, diffuse_sett.init_mat())
+        .expect("Invalid material interfaces.");
+    report!("Interfaces", &inters);
+
+    (
+        tree_sett,
+        grid_sett,
+        diffuse_sett,
+        lights,
+        surfs,
+        mats,
+        inters,
+    )
+}
+
+/// Grow domains.
+fn grow<'a>(
+    tree_sett: tree::Settings,
+    grid_sett: grid::Settings,
+    surfs: &'a Set<Mesh>,
+    mats: &Set<mcrt::Material>,
+) -> (tree::Cell<'a>, grid::Grid) {
+    banner::section("Growing");
+
+    banner::sub_section("Adaptive Tree");
+    let tree = tree::Cell::new_root(&tree_sett, &surfs);
+    report!("Adaptive tree", &tree);
+
+    banner::sub_section("Regular Grid");
+    let grid = grid::Grid::new(&grid_sett);
+    report!("Regular grid", &grid);
+    mcrt::check_volumes(mats, &grid).expect("Invalid material volumes.");
+
+    (tree, grid)
+}
+
+/// Run the diffusion sim.
+fn render(
+    sett: &diffuse::Settings,
+    lights: &Set<mcrt::Light>,
+    mats: &Set<mcrt::Material>,
+    inters: &Set<mcrt::Interface>,
+    tree: &tree::Cell,
+    grid: &grid::Grid,
+) -> diffuse::Output {
+    banner::section("Simulating");
+    banner::sub_section("Discretisation");
+    let sys = diffuse::System::new(sett, grid, mats, inters, tree);
+    report!("Diffusion system", &sys);
+
+    banner::sub_section("Source");
+    let sources = diffuse::illuminate(sett, &sys, lights, mats, inters, tree, grid);
+    report!("Source power", sources.sum() * grid.voxel_vol(), "W");
+
+    banner::sub_section("Solving");
+    let fluence_rate = sys.solve(sett, &sources).expect("Simulation failed.");
+
+    diffuse::Output::new(
+        grid.boundary().clone(),
+        grid.voxel_vol(),
+        fluence_rate,
+        sys.ref_index(),
+        sys.abs_coeff(),
+    )
+}
+
+/// Save the output data.
+fn save(out_dir: &Path, data: diffuse::Output) {
+    banner::section("Saving");
+    banner::sub_section("Main Dump");
+    data.save(&out_dir).expect("Could not save output data.");
+}
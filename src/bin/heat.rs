@@ -0,0 +1,155 @@
+//! Bioheat binary.
+
+use attr::input;
+use dia::*;
+use ndarray::Array3;
+use std::path::{Path, PathBuf};
+
+/// Input parameters.
+#[input]
+struct Parameters {
+    /// Adaptive mesh settings.
+    tree: tree::Settings,
+    /// Regular grid settings.
+    grid: grid::Settings,
+    /// Bioheat runtime settings.
+    sett: heat::Settings,
+    /// Absorbed power density [W/m^3] heat source file, read from its absorption density variable, such as an MCRT output.
+    source: String,
+    /// Surfaces map.
+    surfs: Set<form::Mesh>,
+    /// Tissues map.
+    tissues: Set<heat::Tissue>,
+    /// Material interfaces map.
+    inters: Set<mcrt::Interface>,
+}
+
+/// Main function.
+pub fn main() {
+    banner::title("Heat");
+    let (params_path, in_dir, out_dir) = init();
+    let params = input(&in_dir, &params_path);
+    let (tree_sett, grid_sett, heat_sett, source, surfs, tissues, inters) = build(&in_dir, params);
+    let (tree, grid) = grow(tree_sett, grid_sett, &surfs);
+    let sys = heat::System::new(&heat_sett, &grid, &tissues, &inters, &tree, &source)
+        .expect("Unable to construct bioheat system.");
+    report!("Bioheat system", &sys);
+    let data = render(&sys, &heat_sett);
+    report!("output data", &data);
+    save(&out_dir, data);
+    banner::section("Finished");
+}
+
+/// Initialise the command line arguments and directories.
+fn init() -> (PathBuf, PathBuf, PathBuf) {
+    banner::section("Initialisation");
+    banner::sub_section("Command line arguments");
+    args!(bin_path: PathBuf;
+        params_path: PathBuf
+    );
+    report!("binary path", bin_path.display());
+    report!("parameters path", params_path.display());
+
+    banner::sub_section("Directories");
+    let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
+    report!("input directory", in_dir.display());
+    report!("output directory", out_dir.display());
+
+    (params_path, in_dir, out_dir)
+}
+
+/// Load the input files.
+fn input(in_dir: &Path, params_path: &Path) -> Parameters {
+    banner::section("Input");
+    banner::sub_section("Parameters");
+    let path = in_dir.join(params_path);
+
+    Parameters::load(&path).expect("Could not load parameters file")
+}
+
+/// Build instances.
+#[allow(clippy::type_complexity)]
+fn build(
+    in_dir: &Path,
+    params: Parameters,
+) -> (
+    tree::Settings,
+    grid::Settings,
+    heat::Settings,
+    Array3<f64>,
+    Set<Mesh>,
+    Set<heat::Tissue>,
+    Set<mcrt::Interface>,
+) {
+    banner::section("Building");
+    banner::sub_section("Adaptive Tree Settings");
+    let tree_sett = params.tree;
+    report!("Tree settings", &tree_sett);
+
+    banner::sub_section("Grid Settings");
+    let grid_sett = params.grid;
+    report!("Grid settings", &grid_sett);
+
+    banner::sub_section("Bioheat Settings");
+    let heat_sett = params.sett;
+    report!("Bioheat settings", &heat_sett);
+
+    banner::sub_section("Heat Source");
+    let source_path = in_dir.join(&params.source);
+    report!("Heat source", source_path.display());
+    let source =
+        load_variable(&source_path, "absorption_dens").expect("Unable to load heat source.");
+
+    banner::sub_section("Surfaces");
+    let surfs = params
+        .surfs
+        .build(in_dir)
+        .expect("Unable to build surfaces.");
+    report!("Surfaces", &surfs);
+
+    banner::sub_section("Tissues");
+    let tissues = params.tissues;
+    report!("Tissues", &tissues);
+
+    banner::sub_section("Interfaces");
+    let inters = params.inters;
+    heat::check_tissues(&surfs, &inters, &tissues, heat_sett.init_mat()).expect("Invalid tissues.");
+    report!("Interfaces", &inters);
+
+    (
+        tree_sett, grid_sett, heat_sett, source, surfs, tissues, inters,
+    )
+}
+
+/// Grow domains.
+fn grow<'a>(
+    tree_sett: tree::Settings,
+    grid_sett: grid::Settings,
+    surfs: &'a Set<Mesh>,
+) -> (tree::Cell<'a>, grid::Grid) {
+    banner::section("Growing");
+
+    banner::sub_section("Adaptive Tree");
+    let tree = tree::Cell::new_root(&tree_sett, &surfs);
+    report!("Adaptive tree", &tree);
+
+    banner::sub_section("Regular Grid");
+    let grid = grid::Grid::new(&grid_sett);
+    report!("Regular grid", &grid);
+
+    (tree, grid)
+}
+
+/// Run the bioheat sim.
+fn render(sys: &heat::System, sett: &heat::Settings) -> heat::Output {
+    banner::section("Simulating");
+    banner::sub_section("Heating");
+    heat::solve(sys, sett).expect("Simulation failed.")
+}
+
+/// Save the output data.
+fn save(out_dir: &Path, data: heat::Output) {
+    banner::section("Saving");
+    banner::sub_section("Temperature Snapshots");
+    data.save(&out_dir).expect("Could not save output data.");
+}
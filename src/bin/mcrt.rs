@@ -13,30 +13,37 @@ struct Parameters {
     grid: grid::Settings,
     /// MCRT runtime settings.
     sett: mcrt::Settings,
-    /// Light settings.
-    light: form::Light,
+    /// Light sources map.
+    lights: Set<form::Light>,
     /// Surfaces map.
     surfs: Set<form::Mesh>,
     /// Materials map.
     mats: Set<Redirect<form::Material>>,
+    /// Material interfaces map.
+    inters: Set<mcrt::Interface>,
+    /// Detectors map.
+    dets: Set<mcrt::Detector>,
 }
 
 /// Main function.
 pub fn main() {
     banner::title("MCRT");
-    let (params_path, in_dir, out_dir) = init();
+    let (params_path, in_dir, out_dir, resume_dir) = init();
     let params = input(&in_dir, &params_path);
-    let (tree_sett, grid_sett, mcrt_sett, light, surfs, mats) = build(&in_dir, params);
-    let (tree, grid) = grow(tree_sett, grid_sett, &surfs);
-    let input = mcrt::Input::new(&mcrt_sett, &light, &mats, &tree, &grid, 0);
-    let data = render(&input);
+    let (tree_sett, grid_sett, mcrt_sett, lights, surfs, mats, inters, dets) =
+        build(&in_dir, params);
+    let (tree, grid) = grow(tree_sett, grid_sett, &surfs, &mats);
+    let input = mcrt::Input::new(
+        &mcrt_sett, &lights, &mats, &inters, &dets, &surfs, &tree, &grid,
+    );
+    let data = render(&input, &out_dir.join("checkpoint"), resume_dir.as_deref());
     report!("output data", &data);
     save(&out_dir, data);
     banner::section("Finished");
 }
 
 /// Initialise the command line arguments and directories.
-fn init() -> (PathBuf, PathBuf, PathBuf) {
+fn init() -> (PathBuf, PathBuf, PathBuf, Option<PathBuf>) {
     banner::section("Initialisation");
     banner::sub_section("Command line arguments");
     args!(bin_path: PathBuf;
@@ -44,13 +51,17 @@ fn init() -> (PathBuf, PathBuf, PathBuf) {
     );
     report!("binary path", bin_path.display());
     report!("parameters path", params_path.display());
+    let resume_dir = args::flag("--resume").map(PathBuf::from);
+    if let Some(resume_dir) = &resume_dir {
+        report!("resume directory", resume_dir.display());
+    }
 
     banner::sub_section("Directories");
     let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
     report!("input directory", in_dir.display());
     report!("output directory", out_dir.display());
 
-    (params_path, in_dir, out_dir)
+    (params_path, in_dir, out_dir, resume_dir)
 }
 
 /// Load the input files.
@@ -71,9 +82,11 @@ fn build(
     tree::Settings,
     grid::Settings,
     mcrt::Settings,
-    mcrt::Light,
+    Set<mcrt::Light>,
     Set<Mesh>,
     Set<mcrt::Material>,
+    Set<mcrt::Interface>,
+    Set<mcrt::Detector>,
 ) {
     banner::section("Building");
     banner::sub_section("Adaptive Tree Settings");
@@ -88,9 +101,12 @@ fn build(
     let mcrt_sett = params.sett;
     report!("MCRT settings", &mcrt_sett);
 
-    banner::sub_section("Light");
-    let light = params.light.build(in_dir).expect("Unable to build light.");
-    report!("Light", &light);
+    banner::sub_section("Lights");
+    let lights = params
+        .lights
+        .build(in_dir)
+        .expect("Unable to build lights.");
+    report!("Lights", &lights);
 
     banner::sub_section("Surfaces");
     let surfs = params
@@ -108,7 +124,20 @@ fn build(
         .expect("Unable to build materials.");
     report!("Properties", &mats);
 
-    (tree_sett, grid_sett, mcrt_sett, light, surfs, mats)
+    banner::sub_section("Detectors");
+    let dets = params.dets;
+    mcrt::check_detectors(&surfs, &dets).expect("Invalid detectors.");
+    report!("Detectors", &dets);
+
+    banner::sub_section("Interfaces");
+    let inters = params.inters;
+    mcrt::check_interfaces(&surfs, &inters, &dets, &mats, mcrt_sett.init_mat())
+        .expect("Invalid material interfaces.");
+    report!("Interfaces", &inters);
+
+    (
+        tree_sett, grid_sett, mcrt_sett, lights, surfs, mats, inters, dets,
+    )
 }
 
 /// Grow domains.
@@ -116,6 +145,7 @@ fn grow<'a>(
     tree_sett: tree::Settings,
     grid_sett: grid::Settings,
     surfs: &'a Set<Mesh>,
+    mats: &Set<mcrt::Material>,
 ) -> (tree::Cell<'a>, grid::Grid) {
     banner::section("Growing");
 
@@ -126,15 +156,17 @@ fn grow<'a>(
     banner::sub_section("Regular Grid");
     let grid = grid::Grid::new(&grid_sett);
     report!("Regular grid", &grid);
+    mcrt::check_volumes(mats, &grid).expect("Invalid material volumes.");
 
     (tree, grid)
 }
 
 /// Run the mcrt sim.
-fn render(input: &mcrt::Input) -> mcrt::Output {
+fn render(input: &mcrt::Input, check_dir: &Path, resume_dir: Option<&Path>) -> mcrt::Output {
     banner::section("Simulating");
     banner::sub_section("Main Light");
-    mcrt::run::simulate(&input, mcrt::life::test).expect("Simulation failed.")
+    mcrt::run::simulate(&input, mcrt::life::test, check_dir, resume_dir)
+        .expect("Simulation failed.")
 }
 
 /// Save the output data.
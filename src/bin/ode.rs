@@ -0,0 +1,193 @@
+//! Ordinary differential equation integration binary.
+
+use attr::input;
+use dia::*;
+use ndarray::Array1;
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Input parameters.
+#[input]
+struct Parameters {
+    /// Integration method.
+    integrator: ode::Integrator,
+    /// Start time.
+    start: f64,
+    /// End time.
+    end: f64,
+    /// Initial state.
+    init: Vec<f64>,
+    /// Rate terms.
+    terms: Vec<form::Term>,
+    /// Optional threshold events: state component, threshold value, and whether integration stops.
+    events: Option<Vec<(usize, f64, bool)>>,
+    /// Optional number of uniformly spaced dense output samples.
+    samples: Option<usize>,
+}
+
+/// Runtime parameters.
+pub struct Input {
+    /// Integration method.
+    pub integrator: ode::Integrator,
+    /// Start time.
+    pub start: f64,
+    /// End time.
+    pub end: f64,
+    /// Initial state.
+    pub init: Array1<f64>,
+    /// Rate terms.
+    pub terms: Vec<ode::Term>,
+    /// Events.
+    pub events: Vec<ode::Event>,
+    /// Number of dense output samples.
+    pub samples: Option<usize>,
+}
+
+/// Main function.
+pub fn main() {
+    banner::title("ODE");
+    let (params_path, in_dir, out_dir) = init();
+    let params = input(&in_dir, &params_path);
+    let input = build(&in_dir, params);
+    let sol = simulate(&input);
+    save(&out_dir, &input, &sol);
+    banner::section("Finished");
+}
+
+/// Initialise the command line arguments and directories.
+fn init() -> (PathBuf, PathBuf, PathBuf) {
+    banner::section("Initialisation");
+    banner::sub_section("Command line args");
+    args!(bin_path: PathBuf;
+        params_path: PathBuf
+    );
+    report!("binary path", bin_path.display());
+    report!("parameters path", params_path.display());
+
+    banner::sub_section("Directories");
+    let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
+    report!("input directory", in_dir.display());
+    report!("output directory", out_dir.display());
+
+    (params_path, in_dir, out_dir)
+}
+
+/// Load the input files.
+fn input(in_dir: &Path, params_path: &Path) -> Parameters {
+    banner::section("Input");
+    banner::sub_section("Parameters");
+    let path = in_dir.join(params_path);
+
+    Parameters::load(&path).expect("Could not load parameters file")
+}
+
+/// Build instances.
+fn build(in_dir: &Path, params: Parameters) -> Input {
+    banner::section("Building");
+
+    let integrator = params.integrator;
+    report!("integrator", &integrator);
+    let start = params.start;
+    let end = params.end;
+    report!("start time", start);
+    report!("end time", end);
+    let init = Array1::from(params.init);
+    report_list!(
+        "initial state",
+        init.as_slice().expect("Missing initial state.")
+    );
+
+    let mut terms = Vec::with_capacity(params.terms.len());
+    for term in params.terms {
+        let term = term.build(in_dir).expect("Could not build term.");
+        report!("term", &term);
+        terms.push(term);
+    }
+    ode::check_terms(&terms, init.len()).expect("Invalid terms.");
+
+    let mut events = Vec::new();
+    for (component, value, terminal) in params.events.unwrap_or_default() {
+        assert!(
+            component < init.len(),
+            "Event refers to an unknown state component."
+        );
+        events.push(ode::Event::new_threshold(component, value, terminal));
+    }
+
+    Input {
+        integrator,
+        start,
+        end,
+        init,
+        terms,
+        events,
+        samples: params.samples,
+    }
+}
+
+/// Run the integration.
+fn simulate(input: &Input) -> ode::Solution {
+    banner::section("Simulating");
+
+    let sol = input
+        .integrator
+        .integrate(
+            |t, y| ode::rates(&input.terms, t, y),
+            input.start,
+            input.init.clone(),
+            input.end,
+            &input.events,
+        )
+        .expect("Integration failed.");
+    report!("steps", sol.ts().len() - 1);
+    report!("final time", sol.t());
+    report!("events", sol.occurrences().len());
+
+    sol
+}
+
+/// Save the output data.
+fn save(out_dir: &Path, input: &Input, sol: &ode::Solution) {
+    banner::section("Saving");
+
+    let path = out_dir.join("steps.csv");
+    println!("saving: {}", path.display());
+    sol.save(&path).expect("Failed to write output file.");
+
+    if let Some(samples) = input.samples {
+        assert!(
+            samples >= 2,
+            "At least two dense output samples are required."
+        );
+
+        let path = out_dir.join("samples.csv");
+        println!("saving: {}", path.display());
+        let mut file = File::create(path).expect("Failed to create output file.");
+        let delta = (sol.t() - input.start) / (samples - 1) as f64;
+        for n in 0..samples {
+            let t = delta.mul_add(n as f64, input.start).min(sol.t());
+            let y = sol.sample(t).expect("Sample lies outside the solution.");
+            write!(file, "{:>32}", t).expect("Failed to write output file.");
+            for x in &y {
+                write!(file, ", {:>32}", x).expect("Failed to write output file.");
+            }
+            writeln!(file).expect("Failed to write output file.");
+        }
+    }
+
+    if !sol.occurrences().is_empty() {
+        let path = out_dir.join("events.csv");
+        println!("saving: {}", path.display());
+        let mut file = File::create(path).expect("Failed to create output file.");
+        for occ in sol.occurrences() {
+            write!(file, "{:>8}, {:>32}", occ.event, occ.t).expect("Failed to write output file.");
+            for x in &occ.y {
+                write!(file, ", {:>32}", x).expect("Failed to write output file.");
+            }
+            writeln!(file).expect("Failed to write output file.");
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Photodynamic therapy binary.
+
+use attr::input;
+use dia::*;
+use ndarray::Array3;
+use std::path::{Path, PathBuf};
+
+/// Input parameters.
+#[input]
+struct Parameters {
+    /// Adaptive mesh settings.
+    tree: tree::Settings,
+    /// Regular grid settings.
+    grid: grid::Settings,
+    /// Photodynamic therapy settings.
+    sett: pdt::Settings,
+    /// Treatment light fluence rate field.
+    fluence: form::Fluence,
+    /// Photosensitiser.
+    photosensitiser: Redirect<form::Photosensitiser>,
+    /// Initial photosensitiser concentration [mol/m^3] within each material.
+    uptake: Set<f64>,
+    /// Surfaces map.
+    surfs: Set<form::Mesh>,
+    /// Material interfaces map.
+    inters: Set<mcrt::Interface>,
+}
+
+/// Main function.
+pub fn main() {
+    banner::title("PDT");
+    let (params_path, in_dir, out_dir) = init();
+    let params = input(&in_dir, &params_path);
+    let (tree_sett, grid_sett, pdt_sett, fluence, ps, uptake, surfs, inters) =
+        build(&in_dir, params);
+    let (tree, grid) = grow(tree_sett, grid_sett, &surfs);
+    let groups = mcrt::voxel_materials(
+        &tree,
+        &inters,
+        &grid,
+        pdt_sett.init_mat(),
+        pdt_sett.bump_dist(),
+    );
+    let concs = pdt::uptake_map(&uptake, &groups).expect("Unable to map photosensitiser uptake.");
+    let data = render(&pdt_sett, &ps, &fluence, &concs, grid.voxel_vol());
+    report!("output data", &data);
+    save(&out_dir, data);
+    banner::section("Finished");
+}
+
+/// Initialise the command line arguments and directories.
+fn init() -> (PathBuf, PathBuf, PathBuf) {
+    banner::section("Initialisation");
+    banner::sub_section("Command line arguments");
+    args!(bin_path: PathBuf;
+        params_path: PathBuf
+    );
+    report!("binary path", bin_path.display());
+    report!("parameters path", params_path.display());
+
+    banner::sub_section("Directories");
+    let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
+    report!("input directory", in_dir.display());
+    report!("output directory", out_dir.display());
+
+    (params_path, in_dir, out_dir)
+}
+
+/// Load the input files.
+fn input(in_dir: &Path, params_path: &Path) -> Parameters {
+    banner::section("Input");
+    banner::sub_section("Parameters");
+    let path = in_dir.join(params_path);
+
+    Parameters::load(&path).expect("Could not load parameters file")
+}
+
+/// Build instances.
+#[allow(clippy::type_complexity)]
+fn build(
+    in_dir: &Path,
+    params: Parameters,
+) -> (
+    tree::Settings,
+    grid::Settings,
+    pdt::Settings,
+    Array3<f64>,
+    pdt::Photosensitiser,
+    Set<f64>,
+    Set<Mesh>,
+    Set<mcrt::Interface>,
+) {
+    banner::section("Building");
+    banner::sub_section("Adaptive Tree Settings");
+    let tree_sett = params.tree;
+    report!("Tree settings", &tree_sett);
+
+    banner::sub_section("Grid Settings");
+    let grid_sett = params.grid;
+    report!("Grid settings", &grid_sett);
+
+    banner::sub_section("PDT Settings");
+    let pdt_sett = params.sett;
+    report!("PDT settings", &pdt_sett);
+
+    banner::sub_section("Fluence Rate");
+    report!("Fluence rate", &params.fluence);
+    let fluence = params
+        .fluence
+        .build(in_dir)
+        .expect("Unable to build fluence rate.");
+    assert!(
+        fluence.shape() == grid_sett.res(),
+        "Fluence rate does not match the grid resolution."
+    );
+
+    banner::sub_section("Photosensitiser");
+    let ps = params
+        .photosensitiser
+        .build(in_dir)
+        .expect("Unable to build photosensitiser.")
+        .build(in_dir)
+        .expect("Unable to build photosensitiser.");
+    report!("Photosensitiser", &ps);
+
+    banner::sub_section("Uptake");
+    let uptake = params.uptake;
+    report!("Uptake", &uptake, "mol/m^3");
+
+    banner::sub_section("Surfaces");
+    let surfs = params
+        .surfs
+        .build(in_dir)
+        .expect("Unable to build surfaces.");
+    report!("Surfaces", &surfs);
+
+    banner::sub_section("Interfaces");
+    let inters = params.inters;
+    pdt::check_uptake(&surfs, &inters, &uptake, pdt_sett.init_mat()).expect("Invalid uptake.");
+    report!("Interfaces", &inters);
+
+    (
+        tree_sett, grid_sett, pdt_sett, fluence, ps, uptake, surfs, inters,
+    )
+}
+
+/// Grow domains.
+fn grow<'a>(
+    tree_sett: tree::Settings,
+    grid_sett: grid::Settings,
+    surfs: &'a Set<Mesh>,
+) -> (tree::Cell<'a>, grid::Grid) {
+    banner::section("Growing");
+
+    banner::sub_section("Adaptive Tree");
+    let tree = tree::Cell::new_root(&tree_sett, &surfs);
+    report!("Adaptive tree", &tree);
+
+    banner::sub_section("Regular Grid");
+    let grid = grid::Grid::new(&grid_sett);
+    report!("Regular grid", &grid);
+
+    (tree, grid)
+}
+
+/// Run the photodynamic therapy sim.
+fn render(
+    sett: &pdt::Settings,
+    ps: &pdt::Photosensitiser,
+    fluence: &Array3<f64>,
+    concs: &Array3<f64>,
+    cell_vol: f64,
+) -> pdt::Output {
+    banner::section("Simulating");
+    banner::sub_section("Photosensitiser Kinetics");
+    pdt::simulate(sett, ps, fluence, concs, cell_vol).expect("Simulation failed.")
+}
+
+/// Save the output data.
+fn save(out_dir: &Path, data: pdt::Output) {
+    banner::section("Saving");
+    banner::sub_section("Dose Maps");
+    data.save(&out_dir).expect("Could not save output data.");
+}
@@ -2,7 +2,11 @@
 
 use attr::input;
 use dia::*;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+};
 
 /// Input parameters.
 #[input]
@@ -13,48 +17,44 @@ struct Parameters {
     grid: grid::Settings,
     /// MCRT runtime settings.
     sett: mcrt::Settings,
-    /// Light settings.
-    light: form::Light,
+    /// Simulation stages, run in order.
+    stages: Vec<form::Stage>,
     /// Surfaces map.
     surfs: Set<form::Mesh>,
     /// Materials map.
     mats: Set<Redirect<form::Material>>,
-    /// Point list.
-    points: Vec<[f64; 3]>,
+    /// Material interfaces map.
+    inters: Set<mcrt::Interface>,
+    /// Detectors map.
+    dets: Set<mcrt::Detector>,
 }
 
 /// Main function.
 pub fn main() {
     banner::title("RAMAN");
-    let (params_path, in_dir, out_dir) = init();
+    let (params_path, in_dir, out_dir, resume_dir) = init();
     let params = input(&in_dir, &params_path);
-    let ps = params.points.clone();
-    let (tree_sett, grid_sett, mcrt_sett, light, surfs, mats) = build(&in_dir, params);
-    let (tree, grid) = grow(tree_sett, grid_sett, &surfs);
-    let input = mcrt::Input::new(&mcrt_sett, &light, &mats, &tree, &grid, 0);
-    let data = render(&input);
-    report!("illumination data", &data);
-
-    banner::sub_section("Points");
-    let absorptions = &data.absorptions;
-    let mut points = Vec::with_capacity(ps.len());
-    for p in ps {
-        let pos = Pos3::new(p[X], p[Y], p[Z]);
-        let index = input.grid.gen_index(&pos).unwrap();
-        points.push((pos, absorptions[index]));
-    }
-    let points = mcrt::Light::new_points(points, (*light.spec()).clone(), light.power());
-    report!("Points", &points);
-
-    let input = mcrt::Input::new(&mcrt_sett, &points, &mats, &tree, &grid, 4);
-    let data = render(&input);
-    report!("output data", &data);
-    save(&out_dir, data);
+    let (tree_sett, grid_sett, mcrt_sett, stages, surfs, mats, inters, dets) =
+        build(&in_dir, params);
+    let (tree, grid) = grow(tree_sett, grid_sett, &surfs, &mats);
+    let outputs = render(
+        &stages,
+        &mcrt_sett,
+        &mats,
+        &inters,
+        &dets,
+        &surfs,
+        &tree,
+        &grid,
+        &out_dir.join("checkpoint"),
+        resume_dir.as_deref(),
+    );
+    save(&out_dir, &outputs);
     banner::section("Finished");
 }
 
 /// Initialise the command line arguments and directories.
-fn init() -> (PathBuf, PathBuf, PathBuf) {
+fn init() -> (PathBuf, PathBuf, PathBuf, Option<PathBuf>) {
     banner::section("Initialisation");
     banner::sub_section("Command line arguments");
     args!(bin_path: PathBuf;
@@ -62,13 +62,17 @@ fn init() -> (PathBuf, PathBuf, PathBuf) {
     );
     report!("binary path", bin_path.display());
     report!("parameters path", params_path.display());
+    let resume_dir = args::flag("--resume").map(PathBuf::from);
+    if let Some(resume_dir) = &resume_dir {
+        report!("resume directory", resume_dir.display());
+    }
 
     banner::sub_section("Directories");
     let (in_dir, out_dir) = dir::io_dirs(None, None).expect("Could not initialise directories");
     report!("input directory", in_dir.display());
     report!("output directory", out_dir.display());
 
-    (params_path, in_dir, out_dir)
+    (params_path, in_dir, out_dir, resume_dir)
 }
 
 /// Load the input files.
@@ -89,9 +93,11 @@ fn build(
     tree::Settings,
     grid::Settings,
     mcrt::Settings,
-    mcrt::Light,
+    Vec<mcrt::Stage>,
     Set<Mesh>,
     Set<mcrt::Material>,
+    Set<mcrt::Interface>,
+    Set<mcrt::Detector>,
 ) {
     banner::section("Building");
     banner::sub_section("Adaptive Tree Settings");
@@ -106,9 +112,15 @@ fn build(
     let mcrt_sett = params.sett;
     report!("MCRT settings", &mcrt_sett);
 
-    banner::sub_section("Light");
-    let light = params.light.build(in_dir).expect("Unable to build light.");
-    report!("Light", &light);
+    banner::sub_section("Stages");
+    let stages: Vec<_> = params
+        .stages
+        .into_iter()
+        .map(|stage| stage.build(in_dir).expect("Unable to build stage."))
+        .collect();
+    for stage in &stages {
+        report!("Stage", stage);
+    }
 
     banner::sub_section("Surfaces");
     let surfs = params
@@ -126,7 +138,20 @@ fn build(
         .expect("Unable to build materials.");
     report!("Properties", &mats);
 
-    (tree_sett, grid_sett, mcrt_sett, light, surfs, mats)
+    banner::sub_section("Detectors");
+    let dets = params.dets;
+    mcrt::check_detectors(&surfs, &dets).expect("Invalid detectors.");
+    report!("Detectors", &dets);
+
+    banner::sub_section("Interfaces");
+    let inters = params.inters;
+    mcrt::check_interfaces(&surfs, &inters, &dets, &mats, mcrt_sett.init_mat())
+        .expect("Invalid material interfaces.");
+    report!("Interfaces", &inters);
+
+    (
+        tree_sett, grid_sett, mcrt_sett, stages, surfs, mats, inters, dets,
+    )
 }
 
 /// Grow domains.
@@ -134,6 +159,7 @@ fn grow<'a>(
     tree_sett: tree::Settings,
     grid_sett: grid::Settings,
     surfs: &'a Set<Mesh>,
+    mats: &Set<mcrt::Material>,
 ) -> (tree::Cell<'a>, grid::Grid) {
     banner::section("Growing");
 
@@ -144,20 +170,61 @@ fn grow<'a>(
     banner::sub_section("Regular Grid");
     let grid = grid::Grid::new(&grid_sett);
     report!("Regular grid", &grid);
+    mcrt::check_volumes(mats, &grid).expect("Invalid material volumes.");
 
     (tree, grid)
 }
 
-/// Run the mcrt sim.
-fn render(input: &mcrt::Input) -> mcrt::Output {
+/// Run the mcrt stages.
+#[allow(clippy::too_many_arguments)]
+fn render(
+    stages: &[mcrt::Stage],
+    sett: &mcrt::Settings,
+    mats: &Set<mcrt::Material>,
+    inters: &Set<mcrt::Interface>,
+    dets: &Set<mcrt::Detector>,
+    surfs: &Set<Mesh>,
+    tree: &tree::Cell,
+    grid: &grid::Grid,
+    check_dir: &Path,
+    resume_dir: Option<&Path>,
+) -> BTreeMap<Group, mcrt::Output> {
     banner::section("Simulating");
-    banner::sub_section("Main Light");
-    mcrt::run::simulate(&input, mcrt::life::test).expect("Simulation failed.")
+    let mut outputs = BTreeMap::new();
+    for stage in stages {
+        banner::sub_section(stage.name());
+        let data = mcrt::run_stage(
+            stage,
+            &outputs,
+            sett,
+            mats,
+            inters,
+            dets,
+            surfs,
+            tree,
+            grid,
+            mcrt::life::test,
+            check_dir,
+            resume_dir,
+        )
+        .expect("Simulation failed.");
+        outputs.insert(stage.name().clone(), data);
+    }
+
+    for (name, data) in &outputs {
+        report!(&format!("{} data", name), data);
+    }
+
+    outputs
 }
 
-/// Save the output data.
-fn save(out_dir: &Path, data: mcrt::Output) {
+/// Save the output data of each stage within its own directory.
+fn save(out_dir: &Path, outputs: &BTreeMap<Group, mcrt::Output>) {
     banner::section("Saving");
-    banner::sub_section("Main Dump");
-    data.save(&out_dir).expect("Could not save output data.");
+    for (name, data) in outputs {
+        banner::sub_section(name);
+        let stage_dir = out_dir.join(name);
+        create_dir_all(&stage_dir).expect("Could not create stage output directory.");
+        data.save(&stage_dir).expect("Could not save output data.");
+    }
 }
@@ -0,0 +1,71 @@
+//! Fluence rate form implementation.
+
+use crate::{load_variable, Build, Error, Load};
+use attr::load;
+use ndarray::Array3;
+use physical_constants::SPEED_OF_LIGHT_IN_VACUUM;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable fluence rate field, derived from a saved data cube.
+#[load]
+pub enum Fluence {
+    /// Fluence rate [W/m^2] file.
+    Rate(String),
+    /// Light energy density [J/m^3] file, such as an MCRT output, read from its energy density variable,
+    /// and the refractive index of the medium.
+    Energy(String, f64),
+    /// Absorbed power density [W/m^3] file, such as an MCRT output, read from its absorption density variable,
+    /// and the absorption coefficient [1/m] of the medium.
+    Absorption(String, f64),
+}
+
+impl Build for Fluence {
+    type Inst = Array3<f64>;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(match self {
+            Self::Rate(path) => Array3::load(&in_dir.join(path))?,
+            Self::Energy(path, ref_index) => {
+                if ref_index <= 0.0 {
+                    return Err(Error::Text(
+                        "Refractive index must be positive.".to_string(),
+                    ));
+                }
+                load_variable(&in_dir.join(path), "energy_dens")?
+                    * (SPEED_OF_LIGHT_IN_VACUUM / ref_index)
+            }
+            Self::Absorption(path, abs_coeff) => {
+                if abs_coeff <= 0.0 {
+                    return Err(Error::Text(
+                        "Absorption coefficient must be positive.".to_string(),
+                    ));
+                }
+                load_variable(&in_dir.join(path), "absorption_dens")? / abs_coeff
+            }
+        })
+    }
+}
+
+impl Display for Fluence {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Rate(path) => write!(fmt, "Fluence rate ({})", path),
+            Self::Energy(path, ref_index) => {
+                write!(fmt, "Energy density ({}, n = {})", path, ref_index)
+            }
+            Self::Absorption(path, abs_coeff) => {
+                write!(
+                    fmt,
+                    "Absorption density ({}, mu_a = {} m^-1)",
+                    path, abs_coeff
+                )
+            }
+        }
+    }
+}
@@ -1,17 +1,54 @@
 //! Light form implementation.
 
-use crate::{display_field, display_field_ln, form, Build, Error, Redirect};
+use crate::{
+    display_field, display_field_ln, form, Build, Dir3, Error, Pos3, Profile, Redirect, Vec3,
+};
 use attr::load;
 use std::{
     fmt::{Display, Formatter},
     path::Path,
 };
 
+/// Loadable light emitter geometry.
+#[load]
+pub enum Emitter {
+    /// Mesh surface.
+    Surface(form::Mesh),
+    /// Isotropic point: position [m].
+    Point(Pos3),
+    /// Pencil beam: position [m], direction.
+    Pencil(Pos3, Vec3),
+    /// Collimated top-hat beam: centre [m], direction, radius [m].
+    Beam(Pos3, Vec3, f64),
+    /// Gaussian beam: waist centre [m], direction, 1/e^2 waist radius [m], divergence half-angle [rad].
+    Gaussian(Pos3, Vec3, f64, f64),
+    /// Distant directional source illuminating the grid faces: direction.
+    Directional(Vec3),
+}
+
+impl Display for Emitter {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        let kind = match self {
+            Self::Surface(..) => "Surface",
+            Self::Point(..) => "Point",
+            Self::Pencil(..) => "Pencil",
+            Self::Beam(..) => "Beam",
+            Self::Gaussian(..) => "Gaussian",
+            Self::Directional(..) => "Directional",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
+
 /// Loadable light structure.
 #[load]
 pub struct Light {
-    /// Mesh surface.
-    surf: form::Mesh,
+    /// Emitter geometry.
+    source: Emitter,
+    /// Optional angular emission profile of surface emitters, defaulting to collimated.
+    profile: Option<form::Profile>,
     /// Light spectrum.
     spec: Redirect<form::Probability>,
     /// Light power [J/s].
@@ -23,19 +60,69 @@ impl Build for Light {
 
     #[inline]
     fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
-        let surf = self.surf.build(in_dir)?;
         let spec = self.spec.build(in_dir)?.build(in_dir)?;
         let power = self.power;
+        if power <= 0.0 {
+            return Err(Error::Text("Light power must be positive.".to_string()));
+        }
 
-        Ok(Self::Inst::new(surf, spec, power))
+        let profile = match (self.profile, &self.source) {
+            (None, _) => None,
+            (Some(profile), Emitter::Surface(..)) => Some(profile.build(in_dir)?),
+            (Some(..), _) => {
+                return Err(Error::Text(
+                    "Angular emission profiles apply only to surface emitters.".to_string(),
+                ))
+            }
+        };
+
+        Ok(match self.source {
+            Emitter::Surface(surf) => Self::Inst::new(
+                surf.build(in_dir)?,
+                profile.unwrap_or(Profile::Collimated),
+                spec,
+                power,
+            ),
+            Emitter::Point(pos) => Self::Inst::new_point(pos, spec, power),
+            Emitter::Pencil(pos, dir) => Self::Inst::new_pencil(pos, direction(dir)?, spec, power),
+            Emitter::Beam(pos, dir, radius) => {
+                if radius <= 0.0 {
+                    return Err(Error::Text("Beam radius must be positive.".to_string()));
+                }
+                Self::Inst::new_beam(pos, direction(dir)?, radius, spec, power)
+            }
+            Emitter::Gaussian(pos, dir, waist, divergence) => {
+                if waist <= 0.0 || divergence < 0.0 {
+                    return Err(Error::Text(
+                        "Gaussian beam waist must be positive, and divergence non-negative."
+                            .to_string(),
+                    ));
+                }
+                Self::Inst::new_gaussian(pos, direction(dir)?, waist, divergence, spec, power)
+            }
+            Emitter::Directional(dir) => Self::Inst::new_directional(direction(dir)?, spec, power),
+        })
     }
 }
 
+/// Normalise a loaded direction vector.
+/// # Errors
+/// if the vector has zero length.
+#[inline]
+fn direction(dir: Vec3) -> Result<Dir3, Error> {
+    Dir3::try_new(dir, 1.0e-12).ok_or_else(|| Error::Text("Invalid light direction.".to_string()))
+}
+
 impl Display for Light {
     #[allow(clippy::result_expect_used)]
     #[inline]
     fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
-        display_field_ln!(fmt, "surf", &self.surf)?;
+        display_field_ln!(fmt, "source", &self.source)?;
+        if let Some(profile) = &self.profile {
+            display_field_ln!(fmt, "profile", profile)?;
+        } else {
+            display_field_ln!(fmt, "profile", "none")?;
+        }
         display_field_ln!(fmt, "spec", &self.spec)?;
         display_field!(fmt, "power", self.power, "J/s")
     }
@@ -20,6 +20,12 @@ pub struct Material {
     shift_coeff: Option<form::Formula>,
     /// Asymmetry factor.
     asym_fact: form::Formula,
+    /// Optional scattering phase function, defaulting to Henyey-Greenstein.
+    phase: Option<form::Phase>,
+    /// Optional re-emission following shift events.
+    reemission: Option<form::Reemission>,
+    /// Optional voxel property volume.
+    volume: Option<form::Volume>,
 }
 
 impl Build for Material {
@@ -40,6 +46,22 @@ impl Build for Material {
             None
         };
         let asym_fact = self.asym_fact.build(in_dir)?;
+        let phase = if let Some(phase) = self.phase {
+            phase.build(in_dir)?
+        } else {
+            crate::mcrt::Phase::HenyeyGreenstein
+        };
+        let reemission = if let Some(reemission) = self.reemission {
+            Some(reemission.build(in_dir)?)
+        } else {
+            None
+        };
+
+        let volume = if let Some(volume) = self.volume {
+            Some(volume.build(in_dir)?)
+        } else {
+            None
+        };
 
         Ok(Self::Inst::new(
             ref_index,
@@ -47,6 +69,9 @@ impl Build for Material {
             abs_coeff,
             shift_coeff,
             asym_fact,
+            phase,
+            reemission,
+            volume,
         ))
     }
 }
@@ -63,6 +88,15 @@ impl Display for Material {
         if let Some(shift_coeff) = &self.shift_coeff {
             display_field_ln!(fmt, "shift coefficient", shift_coeff)?;
         }
+        if let Some(reemission) = &self.reemission {
+            display_field_ln!(fmt, "re-emission", reemission)?;
+        }
+        if let Some(phase) = &self.phase {
+            display_field_ln!(fmt, "phase function", phase)?;
+        }
+        if let Some(volume) = &self.volume {
+            display_field_ln!(fmt, "volume", volume)?;
+        }
         display_field!(fmt, "asymmetry factor", &self.asym_fact)
     }
 }
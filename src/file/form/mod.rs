@@ -1,13 +1,23 @@
 //! Form module.
 
+pub mod fluence;
 pub mod formula;
 pub mod gradient;
 pub mod light;
 pub mod material;
 pub mod mesh;
+pub mod phase;
+pub mod photosensitiser;
 pub mod probability;
+pub mod profile;
+pub mod reemission;
+pub mod stage;
+pub mod term;
 pub mod trans3;
+pub mod volume;
 
 pub use self::{
-    formula::*, gradient::*, light::*, material::*, mesh::*, probability::*, trans3::*,
+    fluence::*, formula::*, gradient::*, light::*, material::*, mesh::*, phase::*,
+    photosensitiser::*, probability::*, profile::*, reemission::*, stage::*, term::*, trans3::*,
+    volume::*,
 };
@@ -0,0 +1,157 @@
+//! Phase function form implementation.
+
+use crate::{form, Build, Error};
+use attr::load;
+use ndarray::Array1;
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+/// Loadable scattering phase function.
+#[load]
+pub enum Phase {
+    /// Henyey-Greenstein.
+    HenyeyGreenstein,
+    /// Two-term Henyey-Greenstein: backward asymmetry factor, forward lobe fraction.
+    TwoTermHenyeyGreenstein(form::Formula, form::Formula),
+    /// Modified Henyey-Greenstein: Henyey-Greenstein fraction.
+    ModifiedHenyeyGreenstein(form::Formula),
+    /// Rayleigh.
+    Rayleigh,
+    /// Tabulated: ascending wavelengths [m], and paths to csv files of scattering angle [deg], phase function value.
+    Tabulated(Vec<f64>, Vec<String>),
+}
+
+impl Build for Phase {
+    type Inst = crate::mcrt::Phase;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(match self {
+            Self::HenyeyGreenstein => Self::Inst::HenyeyGreenstein,
+            Self::TwoTermHenyeyGreenstein(back, frac) => {
+                Self::Inst::new_two_term_henyey_greenstein(back.build(in_dir)?, frac.build(in_dir)?)
+            }
+            Self::ModifiedHenyeyGreenstein(frac) => {
+                Self::Inst::new_modified_henyey_greenstein(frac.build(in_dir)?)
+            }
+            Self::Rayleigh => Self::Inst::Rayleigh,
+            Self::Tabulated(wavelengths, paths) => {
+                if wavelengths.is_empty() || (wavelengths.len() != paths.len()) {
+                    return Err(Error::Text(
+                        "Tabulated phase functions require one table per wavelength.".to_string(),
+                    ));
+                }
+                if wavelengths.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(Error::Text(
+                        "Tabulated phase function wavelengths must be strictly ascending."
+                            .to_string(),
+                    ));
+                }
+
+                let mut tables = Vec::with_capacity(paths.len());
+                for path in paths {
+                    tables.push(load_polar_table(&in_dir.join(path))?);
+                }
+
+                Self::Inst::new_tabulated(Array1::from(wavelengths), &tables)
+            }
+        })
+    }
+}
+
+/// Load a tabulated polar distribution from a csv file of angle [deg], value lines.
+/// Angles are converted to radians.
+/// # Errors
+/// if the file can not be read,
+/// a line can not be parsed,
+/// the angles are not strictly ascending within [0, 180] degrees,
+/// a value is negative,
+/// or the distribution integrates to zero.
+#[inline]
+pub fn load_polar_table(path: &Path) -> Result<(Array1<f64>, Array1<f64>), Error> {
+    println!("loading: {}", path.display());
+
+    let mut thetas = Vec::new();
+    let mut ps = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split(',').map(str::trim);
+        let theta = words.next().ok_or("Missing angle word.")?.parse::<f64>()?;
+        let p = words.next().ok_or("Missing value word.")?.parse::<f64>()?;
+
+        if !(0.0..=180.0).contains(&theta) {
+            return Err(Error::Text(format!(
+                "Polar table {} angle {} lies outside of [0, 180] degrees.",
+                path.display(),
+                theta
+            )));
+        }
+        if thetas
+            .last()
+            .map_or(false, |prev| theta.to_radians() <= *prev)
+        {
+            return Err(Error::Text(format!(
+                "Polar table {} angles must be strictly ascending.",
+                path.display()
+            )));
+        }
+        if !(p >= 0.0) {
+            return Err(Error::Text(format!(
+                "Polar table {} value {} must be non-negative.",
+                path.display(),
+                p
+            )));
+        }
+
+        thetas.push(theta.to_radians());
+        ps.push(p);
+    }
+
+    if thetas.len() < 2 {
+        return Err(Error::Text(format!(
+            "Polar table {} requires at least two rows.",
+            path.display()
+        )));
+    }
+
+    // The distribution is sampled per unit solid angle, so only a positive sine-weighted integral can be normalised.
+    let total: f64 = thetas
+        .windows(2)
+        .zip(ps.windows(2))
+        .map(|(theta, p)| {
+            0.5 * p[0].mul_add(theta[0].sin(), p[1] * theta[1].sin()) * (theta[1] - theta[0])
+        })
+        .sum();
+    if !(total > 0.0) {
+        return Err(Error::Text(format!(
+            "Polar table {} must integrate to a positive value.",
+            path.display()
+        )));
+    }
+
+    Ok((Array1::from(thetas), Array1::from(ps)))
+}
+
+impl Display for Phase {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        let kind = match self {
+            Self::HenyeyGreenstein => "Henyey-Greenstein",
+            Self::TwoTermHenyeyGreenstein { .. } => "Two-Term Henyey-Greenstein",
+            Self::ModifiedHenyeyGreenstein { .. } => "Modified Henyey-Greenstein",
+            Self::Rayleigh => "Rayleigh",
+            Self::Tabulated { .. } => "Tabulated",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
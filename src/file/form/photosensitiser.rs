@@ -0,0 +1,53 @@
+//! Photosensitiser form implementation.
+
+use crate::{display_field, display_field_ln, form, Build, Error};
+use attr::load;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable photosensitiser structure.
+#[load]
+pub struct Photosensitiser {
+    /// Decadic molar extinction coefficient [m^2/mol] as a function of wavelength [m].
+    extinction: form::Formula,
+    /// Singlet oxygen quantum yield.
+    singlet_yield: f64,
+    /// Singlet oxygen mediated photobleaching coefficient [m^3/mol].
+    bleaching: f64,
+}
+
+impl Build for Photosensitiser {
+    type Inst = crate::pdt::Photosensitiser;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        if self.singlet_yield < 0.0 || self.singlet_yield > 1.0 {
+            return Err(Error::Text(
+                "Singlet oxygen yield must lie between zero and one.".to_string(),
+            ));
+        }
+        if self.bleaching < 0.0 {
+            return Err(Error::Text(
+                "Photobleaching coefficient must be non-negative.".to_string(),
+            ));
+        }
+
+        Ok(Self::Inst::new(
+            self.extinction.build(in_dir)?,
+            self.singlet_yield,
+            self.bleaching,
+        ))
+    }
+}
+
+impl Display for Photosensitiser {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(fmt, "extinction", &self.extinction, "m^2/mol")?;
+        display_field_ln!(fmt, "singlet oxygen yield", self.singlet_yield)?;
+        display_field!(fmt, "photobleaching", self.bleaching, "m^3/mol")
+    }
+}
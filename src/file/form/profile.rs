@@ -0,0 +1,63 @@
+//! Angular emission profile form implementation.
+
+use crate::{form::load_polar_table, Build, Error};
+use attr::load;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable angular emission profile.
+#[load]
+pub enum Profile {
+    /// Emission along the surface normal.
+    Collimated,
+    /// Cosine-weighted emission.
+    Lambertian,
+    /// Uniform hemisphere emission.
+    Hemisphere,
+    /// Uniform cone emission: half-angle [deg].
+    Cone(f64),
+    /// Tabulated: path to a csv file of polar angle [deg], relative luminous intensity, as given by emitter datasheets.
+    Tabulated(String),
+}
+
+impl Build for Profile {
+    type Inst = crate::Profile;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(match self {
+            Self::Collimated => Self::Inst::Collimated,
+            Self::Lambertian => Self::Inst::Lambertian,
+            Self::Hemisphere => Self::Inst::Hemisphere,
+            Self::Cone(half_angle) => {
+                if half_angle <= 0.0 || half_angle > 180.0 {
+                    return Err(Error::Text(
+                        "Cone half-angle must lie within (0, 180] degrees.".to_string(),
+                    ));
+                }
+                Self::Inst::new_cone(half_angle.to_radians())
+            }
+            Self::Tabulated(path) => {
+                let (thetas, intensities) = load_polar_table(&in_dir.join(path))?;
+                Self::Inst::new_tabulated(&thetas, &intensities)
+            }
+        })
+    }
+}
+
+impl Display for Profile {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        let kind = match self {
+            Self::Collimated => "Collimated",
+            Self::Lambertian => "Lambertian",
+            Self::Hemisphere => "Hemisphere",
+            Self::Cone(..) => "Cone",
+            Self::Tabulated(..) => "Tabulated",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
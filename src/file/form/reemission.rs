@@ -0,0 +1,55 @@
+//! Re-emission form implementation.
+
+use crate::{display_field, display_field_ln, form, Build, Error, Redirect};
+use attr::load;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable inelastic re-emission structure.
+#[load]
+pub struct Reemission {
+    /// Emission spectrum.
+    spec: Redirect<form::Probability>,
+    /// Quantum yield.
+    quantum_yield: f64,
+    /// Optional emission lifetime [s].
+    lifetime: Option<f64>,
+}
+
+impl Build for Reemission {
+    type Inst = crate::mcrt::Reemission;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        if self.quantum_yield < 0.0 || self.quantum_yield > 1.0 {
+            return Err(Error::Text(
+                "Re-emission quantum yield must lie between zero and one.".to_string(),
+            ));
+        }
+        if self.lifetime.map_or(false, |lifetime| lifetime <= 0.0) {
+            return Err(Error::Text(
+                "Re-emission lifetime must be positive.".to_string(),
+            ));
+        }
+
+        let spec = self.spec.build(in_dir)?.build(in_dir)?;
+
+        Ok(Self::Inst::new(spec, self.quantum_yield, self.lifetime))
+    }
+}
+
+impl Display for Reemission {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(fmt, "spec", &self.spec)?;
+        if let Some(lifetime) = self.lifetime {
+            display_field_ln!(fmt, "quantum yield", self.quantum_yield)?;
+            display_field!(fmt, "lifetime", lifetime, "s")
+        } else {
+            display_field!(fmt, "quantum yield", self.quantum_yield)
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Simulation stage form implementation.
+
+use crate::{
+    display_field, display_field_ln, form,
+    mcrt::{Sampling, Tally},
+    Build, Error, Group, Redirect, Set,
+};
+use attr::load;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable stage emission source.
+#[load]
+pub enum Source {
+    /// Fixed light sources.
+    Lights(Set<form::Light>),
+    /// Source derived from the output of a previous stage.
+    Derived {
+        /// Name of the previous stage.
+        from: Group,
+        /// Tally weighting the emission.
+        tally: Tally,
+        /// Emission placement.
+        sampling: Sampling,
+        /// Emission spectrum.
+        spec: Redirect<form::Probability>,
+        /// Power [J/s].
+        power: f64,
+    },
+}
+
+impl Build for Source {
+    type Inst = crate::mcrt::Source;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(match self {
+            Self::Lights(lights) => Self::Inst::Lights(lights.build(in_dir)?),
+            Self::Derived {
+                from,
+                tally,
+                sampling,
+                spec,
+                power,
+            } => {
+                if power <= 0.0 {
+                    return Err(Error::Text(
+                        "Derived source power must be positive.".to_string(),
+                    ));
+                }
+
+                Self::Inst::Derived {
+                    from,
+                    tally,
+                    sampling,
+                    spec: spec.build(in_dir)?.build(in_dir)?,
+                    power,
+                }
+            }
+        })
+    }
+}
+
+impl Display for Source {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Lights(lights) => display_field!(fmt, "lights", lights),
+            Self::Derived {
+                from,
+                tally,
+                sampling,
+                spec,
+                power,
+            } => {
+                display_field_ln!(fmt, "derived from", from)?;
+                display_field_ln!(fmt, "tally", tally)?;
+                display_field_ln!(fmt, "sampling", sampling)?;
+                display_field_ln!(fmt, "spectrum", spec)?;
+                display_field!(fmt, "power", power, "J/s")
+            }
+        }
+    }
+}
+
+/// Loadable simulation stage.
+#[load]
+pub struct Stage {
+    /// Stage name.
+    name: Group,
+    /// Emission source.
+    source: Source,
+}
+
+impl Build for Stage {
+    type Inst = crate::mcrt::Stage;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(Self::Inst::new(self.name, self.source.build(in_dir)?))
+    }
+}
+
+impl Display for Stage {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(fmt, "name", &self.name)?;
+        display_field!(fmt, "source", &self.source)
+    }
+}
@@ -0,0 +1,43 @@
+//! Rate term form implementation.
+
+use crate::{display_field, display_field_ln, form, ode::Arg, Build, Error};
+use attr::load;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable rate term structure.
+#[load]
+pub struct Term {
+    /// Index of the state component the term contributes to.
+    target: usize,
+    /// Coefficient.
+    coeff: f64,
+    /// Factor formulae and their arguments.
+    factors: Vec<(Arg, form::Formula)>,
+}
+
+impl Build for Term {
+    type Inst = crate::ode::Term;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        let mut factors = Vec::with_capacity(self.factors.len());
+        for (arg, formula) in self.factors {
+            factors.push((arg, formula.build(in_dir)?));
+        }
+
+        Ok(Self::Inst::new(self.target, self.coeff, factors))
+    }
+}
+
+impl Display for Term {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(fmt, "target", self.target)?;
+        display_field_ln!(fmt, "coefficient", self.coeff)?;
+        display_field!(fmt, "factors", self.factors.len())
+    }
+}
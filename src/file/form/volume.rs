@@ -0,0 +1,90 @@
+//! Voxel property volume form implementation.
+
+use crate::{display_field, display_field_ln, Build, Error, Load};
+use attr::load;
+use ndarray::Array3;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Loadable voxel property volume.
+/// Each entry is an optional path to a NetCDF (.nc) or NumPy (.npy) array.
+#[load]
+pub struct Volume {
+    /// Optional refractive index array.
+    ref_index: Option<String>,
+    /// Optional scattering coefficient [1/m] array.
+    scat_coeff: Option<String>,
+    /// Optional absorption coefficient [1/m] array.
+    abs_coeff: Option<String>,
+    /// Optional asymmetry factor array.
+    asym_fact: Option<String>,
+}
+
+/// Load an optional property array, checking that each value lies within a valid range.
+/// # Errors
+/// if the array can not be loaded,
+/// or contains an invalid value.
+#[inline]
+fn load_array(
+    in_dir: &Path,
+    path: Option<String>,
+    name: &str,
+    valid: fn(f64) -> bool,
+) -> Result<Option<Array3<f64>>, Error> {
+    if let Some(path) = path {
+        let arr = Array3::<f64>::load(&in_dir.join(path))?;
+        if !arr.iter().all(|x| valid(*x)) {
+            return Err(Error::Text(format!("Invalid {} volume value.", name)));
+        }
+        Ok(Some(arr))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Build for Volume {
+    type Inst = crate::mcrt::Volume;
+
+    #[inline]
+    fn build(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        let ref_index = load_array(in_dir, self.ref_index, "refractive index", |n| n >= 1.0)?;
+        let scat_coeff = load_array(in_dir, self.scat_coeff, "scattering coefficient", |s| {
+            s > 0.0
+        })?;
+        let abs_coeff = load_array(in_dir, self.abs_coeff, "absorption coefficient", |a| {
+            a >= 0.0
+        })?;
+        let asym_fact = load_array(in_dir, self.asym_fact, "asymmetry factor", |g| {
+            g.abs() <= 1.0
+        })?;
+
+        let arrays = [&ref_index, &scat_coeff, &abs_coeff, &asym_fact];
+        let mut shapes = arrays
+            .iter()
+            .filter_map(|arr| arr.as_ref().map(Array3::shape));
+        let shape = shapes
+            .next()
+            .ok_or("Property volumes require at least one array.")?;
+        if shapes.any(|other| other != shape) {
+            return Err(Error::Text(
+                "Property volume arrays must share the same resolution.".to_string(),
+            ));
+        }
+
+        Ok(Self::Inst::new(ref_index, scat_coeff, abs_coeff, asym_fact))
+    }
+}
+
+impl Display for Volume {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        let name = |path: &Option<String>| path.clone().unwrap_or_else(|| "none".to_string());
+        display_field_ln!(fmt, "refractive index", name(&self.ref_index))?;
+        display_field_ln!(fmt, "scattering coefficient", name(&self.scat_coeff))?;
+        display_field_ln!(fmt, "absorption coefficient", name(&self.abs_coeff))?;
+        display_field!(fmt, "asymmetry factor", name(&self.asym_fact))
+    }
+}
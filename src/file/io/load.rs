@@ -1,8 +1,13 @@
 //! Load trait.
 
 use crate::Error;
+use ndarray::{Array3, Ix3, ShapeBuilder};
 use serde::Deserialize;
-use std::{fs::read_to_string, path::Path};
+use std::{
+    convert::TryInto,
+    fs::{read, read_to_string},
+    path::Path,
+};
 
 /// Types implementing this trait can be loaded from a file.
 pub trait Load
@@ -28,3 +33,125 @@ where
     let s = read_to_string(path)?;
     Ok(json5::from_str(&s)?)
 }
+
+impl Load for Array3<f64> {
+    /// Load a three-dimensional array from a NetCDF file, reading its "data" variable,
+    /// or from a little-endian NumPy .npy file of 32 or 64 bit floats.
+    #[inline]
+    fn load(path: &Path) -> Result<Self, Error> {
+        load_variable(path, "data")
+    }
+}
+
+/// Load a three-dimensional array from the named variable of a NetCDF file,
+/// falling back to the "data" variable of a single array file,
+/// or from a little-endian NumPy .npy file of 32 or 64 bit floats.
+/// # Errors
+/// if the file can not be read,
+/// does not hold the variable,
+/// or the array is not three-dimensional.
+#[inline]
+pub fn load_variable(path: &Path, name: &str) -> Result<Array3<f64>, Error> {
+    println!("loading: {}", path.display());
+
+    match path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some("nc") => {
+            let file = netcdf::open(path)?;
+            let var = file
+                .variable(name)
+                .or_else(|| file.variable("data"))
+                .ok_or_else(|| Error::Text(format!("Missing NetCDF variable {}.", name)))?;
+            Ok(var
+                .values::<f64>(None, None)?
+                .into_dimensionality::<Ix3>()?)
+        }
+        Some("npy") => from_npy(path),
+        _ => Err(Error::Text(format!(
+            "Can not load array file {}: expected a .nc or .npy extension.",
+            path.display()
+        ))),
+    }
+}
+
+/// Read a three-dimensional array from a NumPy .npy file.
+/// # Errors
+/// if the file can not be read,
+/// or does not hold a little-endian float array of three dimensions.
+#[inline]
+fn from_npy(path: &Path) -> Result<Array3<f64>, Error> {
+    let bytes = read(path)?;
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" {
+        return Err(Error::Text(format!(
+            "{} is not a .npy file.",
+            path.display()
+        )));
+    }
+
+    let (header_start, header_len) = if bytes[6] == 1 {
+        (10, usize::from(u16::from_le_bytes([bytes[8], bytes[9]])))
+    } else {
+        (
+            12,
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+        )
+    };
+    let data_start = header_start + header_len;
+    let header = std::str::from_utf8(
+        bytes
+            .get(header_start..data_start)
+            .ok_or("Truncated .npy header.")?,
+    )
+    .map_err(|_| "Invalid .npy header.")?;
+
+    let word_size = if header.contains("'<f8'") {
+        8
+    } else if header.contains("'<f4'") {
+        4
+    } else {
+        return Err(Error::Text(format!(
+            "{} must hold little-endian 32 or 64 bit floats.",
+            path.display()
+        )));
+    };
+    let fortran_order = header.contains("'fortran_order': True");
+
+    let shape_start = header.find("'shape': (").ok_or("Missing .npy shape.")? + "'shape': (".len();
+    let shape_end = shape_start
+        + header[shape_start..]
+            .find(')')
+            .ok_or("Missing .npy shape.")?;
+    let shape = header[shape_start..shape_end]
+        .split(',')
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::parse::<usize>)
+        .collect::<Result<Vec<_>, _>>()?;
+    if shape.len() != 3 {
+        return Err(Error::Text(format!(
+            "{} must hold a three-dimensional array.",
+            path.display()
+        )));
+    }
+
+    let data: Vec<f64> = bytes
+        .get(data_start..)
+        .ok_or("Truncated .npy data.")?
+        .chunks_exact(word_size)
+        .map(|word| {
+            if word_size == 8 {
+                f64::from_le_bytes(word.try_into().expect("Invalid word size."))
+            } else {
+                f64::from(f32::from_le_bytes(
+                    word.try_into().expect("Invalid word size."),
+                ))
+            }
+        })
+        .collect();
+
+    let shape = (shape[0], shape[1], shape[2]);
+    Ok(if fortran_order {
+        Array3::from_shape_vec(shape.f(), data)?
+    } else {
+        Array3::from_shape_vec(shape, data)?
+    })
+}
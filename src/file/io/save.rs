@@ -1,7 +1,8 @@
 //! Save trait.
 
-use crate::{Error, X, Y, Z};
-use ndarray::{Array2, Array3, ShapeBuilder};
+use crate::{Aabb, Error, X, Y, Z};
+use ndarray::{Array2, Array3, Array4, ShapeBuilder};
+use netcdf::MutableFile;
 use palette::{LinSrgba, Pixel, Srgba};
 use png::{BitDepth, ColorType, Encoder};
 use serde::Serialize;
@@ -97,3 +98,85 @@ impl Save for Array3<f64> {
         Ok(())
     }
 }
+
+impl Save for Array4<f64> {
+    #[inline]
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut file = netcdf::create(path)?;
+
+        let shape = self.shape();
+
+        let dim1_name = "x";
+        file.add_dimension(dim1_name, shape[X])?;
+        let dim2_name = "y";
+        file.add_dimension(dim2_name, shape[Y])?;
+        let dim3_name = "z";
+        file.add_dimension(dim3_name, shape[Z])?;
+        let dim4_name = "t";
+        file.add_dimension(dim4_name, shape[3])?;
+
+        let mut var =
+            file.add_variable::<f64>("data", &[dim1_name, dim2_name, dim3_name, dim4_name])?;
+        var.put_values(self.as_slice().ok_or("Missing slice data.")?, None, None)?;
+
+        Ok(())
+    }
+}
+
+/// Add a variable, with its units and description, to a NetCDF file.
+/// # Errors
+/// if the variable can not be written.
+#[inline]
+pub fn put_variable(
+    file: &mut MutableFile,
+    name: &str,
+    dims: &[&str],
+    values: &[f64],
+    units: &str,
+    long_name: &str,
+) -> Result<(), Error> {
+    let mut var = file.add_variable::<f64>(name, dims)?;
+    var.add_attribute("units", units)?;
+    var.add_attribute("long_name", long_name)?;
+    var.put_values(values, None, None)?;
+
+    Ok(())
+}
+
+/// Add the x, y and z dimensions of a voxel grid spanning a boundary to a NetCDF file,
+/// with coordinate variables of the voxel centres.
+/// # Errors
+/// if a dimension or variable can not be written.
+#[inline]
+pub fn put_voxel_centres(
+    file: &mut MutableFile,
+    boundary: &Aabb,
+    res: &[usize],
+) -> Result<(), Error> {
+    debug_assert!(res.len() == 3);
+
+    for (axis, name) in ["x", "y", "z"].iter().enumerate() {
+        file.add_dimension(name, res[axis])?;
+        put_variable(
+            file,
+            name,
+            &[name],
+            &centres(boundary.mins()[axis], boundary.maxs()[axis], res[axis]),
+            "m",
+            &format!("voxel centre {} position", name),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Calculate the centres of the bins evenly dividing a range.
+#[inline]
+#[must_use]
+pub fn centres(min: f64, max: f64, bins: usize) -> Vec<f64> {
+    let width = (max - min) / bins as f64;
+
+    (0..bins)
+        .map(|i| (i as f64 + 0.5).mul_add(width, min))
+        .collect()
+}
@@ -71,18 +71,29 @@ impl Grid {
         }
     }
 
+    /// Generate the boundary of the voxel at the given index.
+    #[inline]
+    #[must_use]
+    pub fn voxel(&self, index: [usize; 3]) -> Aabb {
+        debug_assert!(index[X] < self.res[X]);
+        debug_assert!(index[Y] < self.res[Y]);
+        debug_assert!(index[Z] < self.res[Z]);
+
+        let mut min = *self.boundary.mins();
+        min.x += self.voxel_size[X] * index[X] as f64;
+        min.y += self.voxel_size[Y] * index[Y] as f64;
+        min.z += self.voxel_size[Z] * index[Z] as f64;
+
+        Aabb::new(min, min + self.voxel_size)
+    }
+
     /// If the given position is contained within the grid,
     /// generate the index and voxel for the given position within the grid.
     #[inline]
     #[must_use]
     pub fn gen_index_voxel(&self, p: &Pos3) -> Option<([usize; 3], Aabb)> {
         if let Some(index) = self.gen_index(p) {
-            let mut min = *self.boundary.mins();
-            min.x += self.voxel_size[X] * index[X] as f64;
-            min.y += self.voxel_size[Y] * index[Y] as f64;
-            min.z += self.voxel_size[Z] * index[Z] as f64;
-
-            let boundary = Aabb::new(min, min + self.voxel_size);
+            let boundary = self.voxel(index);
             debug_assert!(boundary.contains(p));
 
             Some((index, boundary))
@@ -90,6 +101,28 @@ impl Grid {
             None
         }
     }
+
+    /// Wrap a position which has left the grid back in through the opposite face of each periodic axis.
+    /// Returns true if the wrapped position lies within the grid.
+    #[inline]
+    #[must_use]
+    pub fn wrap(&self, p: &mut Pos3, periodic: &[bool; 3]) -> bool {
+        let mins = self.boundary.mins();
+        let maxs = self.boundary.maxs();
+
+        for dim in 0..3 {
+            if periodic[dim] {
+                let width = maxs[dim] - mins[dim];
+                if p[dim] < mins[dim] {
+                    p[dim] += width;
+                } else if p[dim] > maxs[dim] {
+                    p[dim] -= width;
+                }
+            }
+        }
+
+        self.boundary.contains(p)
+    }
 }
 
 impl Display for Grid {
@@ -1,20 +1,20 @@
 //! Emit trait.
 
 use crate::{Dir3, Pos3, Ray, Vec3};
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::f64::consts::PI;
 
 /// Emit trait implementation.
 /// Types implementing this trait can cast Rays.
 pub trait Emit {
     /// Cast a new ray.
-    fn cast(&self, rng: &mut ThreadRng) -> Ray;
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray;
 }
 
 impl Emit for Pos3 {
     #[inline]
     #[must_use]
-    fn cast(&self, rng: &mut ThreadRng) -> Ray {
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray {
         let theta = rng.gen_range(0.0, 2.0 * PI);
         let z = rng.gen_range(-1.0, 1.0);
 
@@ -12,7 +12,7 @@ pub struct Ray {
 }
 
 impl Ray {
-    access!(pos, Pos3);
+    access!(pos, pos_mut, Pos3);
     access!(dir, dir_mut, Dir3);
 
     /// Construct a new instance.
@@ -54,4 +54,23 @@ impl Ray {
         self.dir = roll_rot * pitch_rot * self.dir;
         self.dir.renormalize();
     }
+
+    /// Rotate the photon, and an attached perpendicular reference frame, with a given roll and subsequent pitch manoeuvre.
+    /// The roll turns the frame about the direction of travel,
+    /// and the pitch then tilts the direction towards the frame.
+    #[inline]
+    pub fn rotate_frame(&mut self, pitch: f64, roll: f64, frame: &mut Dir3) {
+        debug_assert!(self.dir.dot(frame).abs() < 1.0e-6);
+
+        let roll_rot = Rot3::from_axis_angle(&self.dir, roll);
+        *frame = roll_rot * *frame;
+
+        let pitch_axis = Dir3::new_normalize(self.dir.cross(frame));
+        let pitch_rot = Rot3::from_axis_angle(&pitch_axis, pitch);
+
+        self.dir = pitch_rot * self.dir;
+        self.dir.renormalize();
+        *frame = pitch_rot * *frame;
+        frame.renormalize();
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::{access, display_field, display_field_ln, Collide, Pos3, Ray, Side, Trace, Vec3};
 use attr::load;
+use serde::Serialize;
 use std::{
     cmp::Ordering,
     fmt::{Display, Formatter, Result},
@@ -10,7 +11,7 @@ use std::{
 /// Axis-aligned bounding box geometry.
 /// Used for spatial partitioning.
 #[load]
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Aabb {
     /// Minimum bound.
     mins: Pos3,
@@ -2,10 +2,11 @@
 
 use crate::{
     access, clone, display_field, display_field_ln, Aabb, Collide, Dir3, Emit, Error, Load, Pos3,
-    Ray, Side, SmoothTriangle, Trace, Trans3, Transform, Vec3, ALPHA, X,
+    Profile, Ray, Side, SmoothTriangle, Trace, Trans3, Transform, Vec3, ALPHA, X,
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::{
+    f64::consts::PI,
     fmt::{Display, Formatter},
     fs::File,
     io::{BufRead, BufReader},
@@ -78,6 +79,40 @@ impl Mesh {
     pub fn into_tris(self) -> Vec<SmoothTriangle> {
         self.tris
     }
+
+    /// Cast a new ray, with a direction sampled from an angular profile about the local surface normal.
+    #[inline]
+    #[must_use]
+    pub fn cast_profile<R: Rng>(&self, profile: &Profile, rng: &mut R) -> Ray {
+        let mut ray = self.cast(rng);
+
+        if let Profile::Collimated = profile {
+            return ray;
+        }
+
+        let theta = profile.gen(rng);
+        let phi = rng.gen_range(0.0, 2.0 * PI);
+        ray.rotate(theta, phi);
+
+        ray
+    }
+
+    /// Cast a new ray from a point sampled uniformly over the surface area,
+    /// directed along the flat plane normal of the triangle it lies upon.
+    #[inline]
+    #[must_use]
+    pub fn cast_flat<R: Rng>(&self, rng: &mut R) -> Ray {
+        let r = rng.gen_range(0.0, self.area);
+        let mut total_area = 0.0;
+        for tri in &self.tris {
+            total_area += tri.tri().area();
+            if total_area >= r {
+                return tri.tri().cast(rng);
+            }
+        }
+
+        unreachable!()
+    }
 }
 
 impl Collide for Mesh {
@@ -112,7 +147,7 @@ impl Transform for Mesh {
 impl Emit for Mesh {
     #[inline]
     #[must_use]
-    fn cast(&self, rng: &mut ThreadRng) -> Ray {
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray {
         let r = rng.gen_range(0.0, self.area);
         let mut total_area = 0.0;
         for tri in &self.tris {
@@ -4,7 +4,7 @@ use crate::{
     access, Aabb, Collide, Dir3, Emit, Pos3, Ray, Side, Trace, Trans3, Transform, Triangle, ALPHA,
     BETA, GAMMA,
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Triangle geometry with normal interpolation.
 pub struct SmoothTriangle {
@@ -91,7 +91,7 @@ impl Transform for SmoothTriangle {
 impl Emit for SmoothTriangle {
     #[inline]
     #[must_use]
-    fn cast(&self, rng: &mut ThreadRng) -> Ray {
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray {
         let mut u = rng.gen::<f64>();
         let mut v = rng.gen::<f64>();
 
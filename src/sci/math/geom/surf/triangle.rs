@@ -4,7 +4,7 @@ use crate::{
     access, Aabb, Collide, Dir3, Emit, Pos3, Ray, Side, Trace, Trans3, Transform, Vec3, ALPHA,
     BETA, GAMMA,
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Triangle.
 pub struct Triangle {
@@ -258,7 +258,7 @@ impl Transform for Triangle {
 impl Emit for Triangle {
     #[inline]
     #[must_use]
-    fn cast(&self, rng: &mut ThreadRng) -> Ray {
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray {
         let mut u = rng.gen::<f64>();
         let mut v = rng.gen::<f64>();
 
@@ -3,8 +3,10 @@
 pub mod alias;
 pub mod func;
 pub mod geom;
+pub mod ode;
 pub mod rng;
 pub mod sample;
 pub mod sort;
+pub mod stencil;
 
-pub use self::{alias::*, func::*, geom::*, rng::*, sample::*, sort::*};
+pub use self::{alias::*, func::*, geom::*, rng::*, sample::*, sort::*, stencil::*};
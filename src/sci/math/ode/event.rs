@@ -0,0 +1,57 @@
+//! Event detection implementation.
+
+use ndarray::Array1;
+
+/// Event structure, triggered when its condition changes sign.
+pub struct Event {
+    /// Condition function of time and state.
+    cond: Box<dyn Fn(f64, &Array1<f64>) -> f64>,
+    /// When true the integration stops at the event.
+    terminal: bool,
+}
+
+impl Event {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new<G>(cond: G, terminal: bool) -> Self
+    where
+        G: Fn(f64, &Array1<f64>) -> f64 + 'static,
+    {
+        Self {
+            cond: Box::new(cond),
+            terminal,
+        }
+    }
+
+    /// Construct a new instance triggered when a state component crosses a threshold value.
+    #[inline]
+    #[must_use]
+    pub fn new_threshold(component: usize, value: f64, terminal: bool) -> Self {
+        Self::new(move |_t, y| y[component] - value, terminal)
+    }
+
+    /// Check if the integration stops at the event.
+    #[inline]
+    #[must_use]
+    pub const fn terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// Evaluate the condition.
+    #[inline]
+    #[must_use]
+    pub fn cond(&self, t: f64, y: &Array1<f64>) -> f64 {
+        (self.cond)(t, y)
+    }
+}
+
+/// Event occurrence structure.
+pub struct Occurrence {
+    /// Index of the triggered event.
+    pub event: usize,
+    /// Time of the occurrence.
+    pub t: f64,
+    /// State at the occurrence.
+    pub y: Array1<f64>,
+}
@@ -0,0 +1,215 @@
+//! Integrator implementation.
+
+use crate::{
+    ode::{dopri_step, newton, rk4_step, Event, Solution},
+    Error,
+};
+use attr::load;
+use ndarray::Array1;
+use std::fmt::{Display, Formatter, Result};
+
+/// Safety factor applied to adaptive step size estimates.
+const SAFETY: f64 = 0.9;
+/// Smallest factor by which an adaptive step may shrink.
+const MIN_SCALE: f64 = 0.2;
+/// Largest factor by which an adaptive step may grow.
+const MAX_SCALE: f64 = 5.0;
+
+/// Ordinary differential equation integrator enumeration.
+#[load]
+pub enum Integrator {
+    /// Fixed step classical fourth-order Runge-Kutta.
+    Rk4 {
+        /// Step size.
+        step: f64,
+    },
+    /// Adaptive step Dormand-Prince fifth-order Runge-Kutta.
+    Rk45 {
+        /// Error tolerance per step, relative to the state magnitude.
+        tolerance: f64,
+        /// Initial step size.
+        init_step: f64,
+        /// Minimum step size.
+        min_step: f64,
+    },
+    /// Fixed step, stiffly stable, second-order backward differentiation.
+    Bdf2 {
+        /// Step size.
+        step: f64,
+        /// Newton iteration tolerance.
+        tolerance: f64,
+        /// Maximum Newton iterations per step.
+        max_iters: u64,
+    },
+}
+
+impl Integrator {
+    /// Integrate the system dy/dt = f(t, y) from the initial state until the end time, or a terminal event.
+    /// # Errors
+    /// if the end time does not follow the start time,
+    /// a step size is not positive,
+    /// an adaptive step falls below its minimum,
+    /// or an implicit step fails to converge.
+    #[inline]
+    pub fn integrate<F>(
+        &self,
+        f: F,
+        t0: f64,
+        y0: Array1<f64>,
+        t1: f64,
+        events: &[Event],
+    ) -> std::result::Result<Solution, Error>
+    where
+        F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+    {
+        if t1 <= t0 {
+            return Err(Error::Text(
+                "Integration end time must follow the start time.".to_string(),
+            ));
+        }
+
+        let mut h = match *self {
+            Self::Rk4 { step } | Self::Bdf2 { step, .. } => step,
+            Self::Rk45 { init_step, .. } => init_step,
+        };
+        if h <= 0.0 {
+            return Err(Error::Text("Step size must be positive.".to_string()));
+        }
+
+        let dy0 = f(t0, &y0);
+        let mut sol = Solution::new(t0, y0, dy0);
+        while sol.t() < t1 {
+            let (t, y, dy) = self.advance(&f, &sol, &mut h, t1)?;
+            if sol.push(&f, t, y, dy, events) {
+                break;
+            }
+        }
+
+        Ok(sol)
+    }
+
+    /// Advance the solution by a single step, not exceeding the end time.
+    /// Adaptive integrators update the step size for the next step.
+    /// # Errors
+    /// if an adaptive step falls below its minimum,
+    /// or an implicit step fails to converge.
+    #[inline]
+    fn advance<F>(
+        &self,
+        f: &F,
+        sol: &Solution,
+        h: &mut f64,
+        t1: f64,
+    ) -> std::result::Result<(f64, Array1<f64>, Array1<f64>), Error>
+    where
+        F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+    {
+        let t = sol.t();
+        let y = sol.y();
+
+        match *self {
+            Self::Rk4 { .. } => {
+                let (t_next, step) = clamp(t, *h, t1);
+                let y_next = rk4_step(f, t, y, step);
+                let dy_next = f(t_next, &y_next);
+                Ok((t_next, y_next, dy_next))
+            }
+            Self::Rk45 {
+                tolerance,
+                min_step,
+                ..
+            } => loop {
+                let (t_next, step) = clamp(t, *h, t1);
+                let (y_next, dy_next, err) = dopri_step(f, t, y, sol.dy(), step);
+
+                let err_norm = err
+                    .iter()
+                    .zip(y.iter().zip(y_next.iter()))
+                    .map(|(e, (a, b))| e.abs() / (1.0 + a.abs().max(b.abs())))
+                    .fold(0.0, f64::max)
+                    / tolerance;
+
+                let scale = if err_norm > 0.0 {
+                    (SAFETY * err_norm.powf(-0.2)).max(MIN_SCALE).min(MAX_SCALE)
+                } else {
+                    MAX_SCALE
+                };
+
+                if err_norm <= 1.0 {
+                    *h = step * scale;
+                    return Ok((t_next, y_next, dy_next));
+                }
+
+                *h = step * scale;
+                if *h < min_step {
+                    return Err(Error::Text(format!(
+                        "Adaptive step size fell below its minimum at t = {}.",
+                        t
+                    )));
+                }
+            },
+            Self::Bdf2 {
+                tolerance,
+                max_iters,
+                ..
+            } => {
+                let (t_next, step) = clamp(t, *h, t1);
+                let guess = y + &(sol.dy() * step);
+
+                // The first step is backward Euler; subsequent steps use the variable step BDF2 formula.
+                let y_next = if let Some((t_prev, y_prev)) = sol.prev() {
+                    let ratio = step / (t - t_prev);
+                    let denom = 2.0f64.mul_add(ratio, 1.0);
+                    let a =
+                        y * ((1.0 + ratio).powi(2) / denom) - &(y_prev * (ratio * ratio / denom));
+                    let bh = step * (1.0 + ratio) / denom;
+                    newton(f, t_next, guess, &a, bh, tolerance, max_iters)?
+                } else {
+                    newton(f, t_next, guess, y, step, tolerance, max_iters)?
+                };
+                let dy_next = f(t_next, &y_next);
+                Ok((t_next, y_next, dy_next))
+            }
+        }
+    }
+}
+
+/// Limit a step so as not to pass the end time.
+/// Returns the time at the end of the step, and the step size.
+#[inline]
+#[must_use]
+fn clamp(t: f64, h: f64, t1: f64) -> (f64, f64) {
+    if t + h >= t1 {
+        (t1, t1 - t)
+    } else {
+        (t + h, h)
+    }
+}
+
+impl Display for Integrator {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Rk4 { step } => write!(fmt, "RK4 (step: {})", step),
+            Self::Rk45 {
+                tolerance,
+                init_step,
+                min_step,
+            } => write!(
+                fmt,
+                "RK45 (tolerance: {}, initial step: {}, minimum step: {})",
+                tolerance, init_step, min_step
+            ),
+            Self::Bdf2 {
+                step,
+                tolerance,
+                max_iters,
+            } => write!(
+                fmt,
+                "BDF2 (step: {}, tolerance: {}, max iterations: {})",
+                step, tolerance, max_iters
+            ),
+        }
+    }
+}
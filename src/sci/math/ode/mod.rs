@@ -0,0 +1,9 @@
+//! Ordinary differential equation module.
+
+pub mod event;
+pub mod integrator;
+pub mod solution;
+pub mod step;
+pub mod term;
+
+pub use self::{event::*, integrator::*, solution::*, step::*, term::*};
@@ -0,0 +1,220 @@
+//! Solution implementation.
+
+use crate::{
+    ode::{Event, Occurrence},
+    Error, Save,
+};
+use ndarray::Array1;
+use std::{fs::File, io::Write, path::Path};
+
+/// Number of bisections used to locate an event.
+const EVENT_BISECTIONS: usize = 64;
+
+/// Integrated solution structure, with dense output between steps.
+pub struct Solution {
+    /// Step times.
+    ts: Vec<f64>,
+    /// Step states.
+    ys: Vec<Array1<f64>>,
+    /// Step state derivatives.
+    dys: Vec<Array1<f64>>,
+    /// Event occurrences.
+    occurrences: Vec<Occurrence>,
+}
+
+impl Solution {
+    /// Construct a new instance at the initial conditions.
+    #[inline]
+    #[must_use]
+    pub fn new(t: f64, y: Array1<f64>, dy: Array1<f64>) -> Self {
+        debug_assert!(y.len() == dy.len());
+
+        Self {
+            ts: vec![t],
+            ys: vec![y],
+            dys: vec![dy],
+            occurrences: Vec::new(),
+        }
+    }
+
+    /// Reference the step times.
+    #[inline]
+    #[must_use]
+    pub fn ts(&self) -> &[f64] {
+        &self.ts
+    }
+
+    /// Reference the step states.
+    #[inline]
+    #[must_use]
+    pub fn ys(&self) -> &[Array1<f64>] {
+        &self.ys
+    }
+
+    /// Reference the event occurrences.
+    #[inline]
+    #[must_use]
+    pub fn occurrences(&self) -> &[Occurrence] {
+        &self.occurrences
+    }
+
+    /// Get the time of the final step.
+    #[inline]
+    #[must_use]
+    pub fn t(&self) -> f64 {
+        self.ts[self.ts.len() - 1]
+    }
+
+    /// Reference the state of the final step.
+    #[inline]
+    #[must_use]
+    pub fn y(&self) -> &Array1<f64> {
+        &self.ys[self.ys.len() - 1]
+    }
+
+    /// Reference the state derivative of the final step.
+    #[inline]
+    #[must_use]
+    pub fn dy(&self) -> &Array1<f64> {
+        &self.dys[self.dys.len() - 1]
+    }
+
+    /// Reference the state of the step before last, if there is one.
+    #[inline]
+    #[must_use]
+    pub fn prev(&self) -> Option<(f64, &Array1<f64>)> {
+        let n = self.ts.len();
+        if n < 2 {
+            return None;
+        }
+        Some((self.ts[n - 2], &self.ys[n - 2]))
+    }
+
+    /// Interpolate the state at a time within the given step, using cubic Hermite interpolation.
+    #[inline]
+    #[must_use]
+    fn interpolate(&self, index: usize, t: f64) -> Array1<f64> {
+        let (t0, t1) = (self.ts[index], self.ts[index + 1]);
+        let h = t1 - t0;
+        let s = (t - t0) / h;
+        let s2 = s * s;
+        let s3 = s2 * s;
+
+        let h00 = 2.0f64.mul_add(s3, -3.0 * s2) + 1.0;
+        let h10 = s3 - (2.0 * s2) + s;
+        let h01 = (-2.0f64).mul_add(s3, 3.0 * s2);
+        let h11 = s3 - s2;
+
+        &self.ys[index] * h00
+            + &(&self.dys[index] * (h10 * h))
+            + &(&self.ys[index + 1] * h01)
+            + &(&self.dys[index + 1] * (h11 * h))
+    }
+
+    /// Sample the dense output at a time.
+    /// Returns None if the time lies outside the integrated range.
+    #[inline]
+    #[must_use]
+    pub fn sample(&self, t: f64) -> Option<Array1<f64>> {
+        let first = self.ts[0];
+        if t < first || t > self.t() {
+            return None;
+        }
+        if self.ts.len() == 1 {
+            return Some(self.ys[0].clone());
+        }
+
+        let index = match self
+            .ts
+            .binary_search_by(|x| x.partial_cmp(&t).expect("Invalid time."))
+        {
+            Ok(index) => return Some(self.ys[index].clone()),
+            Err(index) => index - 1,
+        };
+
+        Some(self.interpolate(index, t))
+    }
+
+    /// Append a step, checking each event for a change of sign across it.
+    /// If a terminal event occurs the step is truncated at the event, and true is returned.
+    #[inline]
+    pub fn push<F>(
+        &mut self,
+        f: &F,
+        t: f64,
+        y: Array1<f64>,
+        dy: Array1<f64>,
+        events: &[Event],
+    ) -> bool
+    where
+        F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+    {
+        debug_assert!(t > self.t());
+
+        let (t0, y0) = (self.t(), self.y().clone());
+        self.ts.push(t);
+        self.ys.push(y);
+        self.dys.push(dy);
+        let index = self.ts.len() - 2;
+
+        // Locate every event triggered within the step.
+        let mut triggered = Vec::new();
+        for (event_index, event) in events.iter().enumerate() {
+            let g0 = event.cond(t0, &y0);
+            let g1 = event.cond(t, &self.ys[index + 1]);
+            if g0 == 0.0 || ((g0 > 0.0) == (g1 > 0.0) && g1 != 0.0) {
+                continue;
+            }
+
+            let (mut lo, mut hi) = (t0, t);
+            for _ in 0..EVENT_BISECTIONS {
+                let mid = (lo + hi) * 0.5;
+                let gm = event.cond(mid, &self.interpolate(index, mid));
+                if (gm > 0.0) == (g0 > 0.0) && gm != 0.0 {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            triggered.push((event_index, hi));
+        }
+        triggered.sort_by(|a, b| a.1.partial_cmp(&b.1).expect("Invalid event time."));
+
+        // Record occurrences, up to and including the first terminal event.
+        for (event_index, te) in triggered {
+            let ye = self.interpolate(index, te);
+            self.occurrences.push(Occurrence {
+                event: event_index,
+                t: te,
+                y: ye.clone(),
+            });
+
+            if events[event_index].terminal() {
+                let dye = f(te, &ye);
+                *self.ts.last_mut().expect("Missing step.") = te;
+                *self.ys.last_mut().expect("Missing step.") = ye;
+                *self.dys.last_mut().expect("Missing step.") = dye;
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Save for Solution {
+    #[inline]
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+
+        for (t, y) in self.ts.iter().zip(&self.ys) {
+            write!(file, "{:>32}", t)?;
+            for x in y {
+                write!(file, ", {:>32}", x)?;
+            }
+            writeln!(file)?;
+        }
+
+        Ok(())
+    }
+}
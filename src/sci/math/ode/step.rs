@@ -0,0 +1,141 @@
+//! Single step integration functions.
+
+use crate::Error;
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+
+/// Relative perturbation used to form finite difference Jacobians.
+const JACOBIAN_DELTA: f64 = 1.0e-8;
+
+/// Take a single classical fourth-order Runge-Kutta step of size h.
+#[inline]
+#[must_use]
+pub fn rk4_step<F>(f: &F, t: f64, y: &Array1<f64>, h: f64) -> Array1<f64>
+where
+    F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+{
+    let half_h = h * 0.5;
+
+    let k1 = f(t, y);
+    let k2 = f(t + half_h, &(y + &(&k1 * half_h)));
+    let k3 = f(t + half_h, &(y + &(&k2 * half_h)));
+    let k4 = f(t + h, &(y + &(&k3 * h)));
+
+    y + &((k1 + &(k2 * 2.0) + &(k3 * 2.0) + &k4) * (h / 6.0))
+}
+
+/// Take a single Dormand-Prince step of size h, given the derivative dy at the start of the step.
+/// Returns the fifth-order solution, its derivative, and the embedded fourth-order error estimate.
+#[inline]
+#[must_use]
+pub fn dopri_step<F>(
+    f: &F,
+    t: f64,
+    y: &Array1<f64>,
+    dy: &Array1<f64>,
+    h: f64,
+) -> (Array1<f64>, Array1<f64>, Array1<f64>)
+where
+    F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+{
+    let k1 = dy;
+    let k2 = f(t + (h / 5.0), &(y + &(k1 * (h / 5.0))));
+    let k3 = f(
+        t + (h * 3.0 / 10.0),
+        &(y + &((k1 * (3.0 / 40.0) + &(&k2 * (9.0 / 40.0))) * h)),
+    );
+    let k4 = f(
+        t + (h * 4.0 / 5.0),
+        &(y + &((k1 * (44.0 / 45.0) - &(&k2 * (56.0 / 15.0)) + &(&k3 * (32.0 / 9.0))) * h)),
+    );
+    let k5 = f(
+        t + (h * 8.0 / 9.0),
+        &(y + &((k1 * (19372.0 / 6561.0) - &(&k2 * (25360.0 / 2187.0))
+            + &(&k3 * (64448.0 / 6561.0))
+            - &(&k4 * (212.0 / 729.0)))
+            * h)),
+    );
+    let k6 = f(
+        t + h,
+        &(y + &((k1 * (9017.0 / 3168.0) - &(&k2 * (355.0 / 33.0))
+            + &(&k3 * (46732.0 / 5247.0))
+            + &(&k4 * (49.0 / 176.0))
+            - &(&k5 * (5103.0 / 18656.0)))
+            * h)),
+    );
+    let y_next =
+        y + &((k1 * (35.0 / 384.0) + &(&k3 * (500.0 / 1113.0)) + &(&k4 * (125.0 / 192.0))
+            - &(&k5 * (2187.0 / 6784.0))
+            + &(&k6 * (11.0 / 84.0)))
+            * h);
+    let k7 = f(t + h, &y_next);
+
+    let err = (k1 * (71.0 / 57600.0) - &(&k3 * (71.0 / 16695.0)) + &(&k4 * (71.0 / 1920.0))
+        - &(&k5 * (17253.0 / 339_200.0))
+        + &(&k6 * (22.0 / 525.0))
+        - &(&k7 * (1.0 / 40.0)))
+        * h;
+
+    (y_next, k7, err)
+}
+
+/// Solve the implicit equation `y = a + (bh * f(t, y))` by Newton iteration from an initial guess,
+/// using a finite difference Jacobian.
+/// # Errors
+/// if the Jacobian is singular,
+/// or the iteration does not converge to within the tolerance.
+#[inline]
+pub fn newton<F>(
+    f: &F,
+    t: f64,
+    guess: Array1<f64>,
+    a: &Array1<f64>,
+    bh: f64,
+    tolerance: f64,
+    max_iters: u64,
+) -> Result<Array1<f64>, Error>
+where
+    F: Fn(f64, &Array1<f64>) -> Array1<f64>,
+{
+    debug_assert!(tolerance > 0.0);
+    debug_assert!(guess.len() == a.len());
+
+    let n = guess.len();
+    let mut y = guess;
+    for _ in 0..max_iters {
+        let fy = f(t, &y);
+        let residual = &y - a - &(&fy * bh);
+
+        // Jacobian of the residual: I - bh * df/dy.
+        let mut jac = DMatrix::<f64>::identity(n, n);
+        for j in 0..n {
+            let delta = JACOBIAN_DELTA * y[j].abs().max(1.0);
+            let mut y_delta = y.clone();
+            y_delta[j] += delta;
+            let df = (f(t, &y_delta) - &fy) / delta;
+            for i in 0..n {
+                jac[(i, j)] -= bh * df[i];
+            }
+        }
+
+        let rhs = DVector::from_iterator(n, residual.iter().map(|r| -r));
+        let dy = jac
+            .lu()
+            .solve(&rhs)
+            .ok_or("Singular Jacobian in implicit step.")?;
+
+        let mut max_change: f64 = 0.0;
+        for (yi, dyi) in y.iter_mut().zip(dy.iter()) {
+            *yi += dyi;
+            max_change = max_change.max(dyi.abs());
+        }
+        if max_change <= tolerance {
+            return Ok(y);
+        }
+    }
+
+    Err(Error::Text(format!(
+        "Implicit step at t = {} did not converge within {} iterations.",
+        t, max_iters
+    )))
+}
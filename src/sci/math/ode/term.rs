@@ -0,0 +1,117 @@
+//! Rate term implementation.
+
+use crate::{Error, Formula};
+use attr::load;
+use ndarray::Array1;
+use std::fmt::{Display, Formatter, Result};
+
+/// Term factor argument enumeration.
+#[load]
+#[derive(Clone, Copy)]
+pub enum Arg {
+    /// Integration time.
+    Time,
+    /// State vector component.
+    State(usize),
+}
+
+impl Arg {
+    /// Select the argument value.
+    #[inline]
+    #[must_use]
+    pub fn select(self, t: f64, y: &Array1<f64>) -> f64 {
+        match self {
+            Self::Time => t,
+            Self::State(index) => y[index],
+        }
+    }
+}
+
+impl Display for Arg {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Time => write!(fmt, "t"),
+            Self::State(index) => write!(fmt, "y[{}]", index),
+        }
+    }
+}
+
+/// Rate term structure, contributing the coefficient multiplied by the product of its factor formulae.
+pub struct Term {
+    /// Index of the state component the term contributes to.
+    target: usize,
+    /// Coefficient.
+    coeff: f64,
+    /// Factor formulae and their arguments.
+    factors: Vec<(Arg, Formula)>,
+}
+
+impl Term {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(target: usize, coeff: f64, factors: Vec<(Arg, Formula)>) -> Self {
+        Self {
+            target,
+            coeff,
+            factors,
+        }
+    }
+
+    /// Evaluate the term.
+    #[inline]
+    #[must_use]
+    pub fn rate(&self, t: f64, y: &Array1<f64>) -> f64 {
+        self.factors.iter().fold(self.coeff, |r, (arg, formula)| {
+            r * formula.y(arg.select(t, y))
+        })
+    }
+}
+
+impl Display for Term {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        write!(fmt, "dy[{}]/dt += {}", self.target, self.coeff)?;
+        for (arg, _formula) in &self.factors {
+            write!(fmt, " * f({})", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sum the rate terms into the state derivative.
+#[inline]
+#[must_use]
+pub fn rates(terms: &[Term], t: f64, y: &Array1<f64>) -> Array1<f64> {
+    let mut dy = Array1::zeros(y.len());
+    for term in terms {
+        dy[term.target] += term.rate(t, y);
+    }
+    dy
+}
+
+/// Check that every term refers to a component of a state vector of the given length.
+/// # Errors
+/// if a term targets, or takes an argument from, a component beyond the state vector.
+#[inline]
+pub fn check_terms(terms: &[Term], len: usize) -> std::result::Result<(), Error> {
+    for term in terms {
+        let mut indices = vec![term.target];
+        for (arg, _formula) in &term.factors {
+            if let Arg::State(index) = arg {
+                indices.push(*index);
+            }
+        }
+        if let Some(index) = indices.into_iter().find(|index| *index >= len) {
+            return Err(Error::Text(format!(
+                "Term refers to state component {} of a {} component system.",
+                index, len
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,68 @@
 //! Distribution functions.
 
-use crate::{Dir3, Vec3};
-use rand::{rngs::ThreadRng, Rng};
-use std::f64::consts::PI;
+use crate::{Dir3, Formula, Vec3};
+use ndarray::Array1;
+use rand::Rng;
+use std::f64::{
+    consts::{PI, SQRT_2},
+    EPSILON, INFINITY, NEG_INFINITY,
+};
+
+/// Argument above which the complementary error function is evaluated by continued fraction rather than series.
+const ERFC_SPLIT: f64 = 2.5;
+
+/// Argument above which the complementary error function underflows.
+const ERFC_MAX: f64 = 27.0;
+
+/// Inverse normal cumulative distribution central region numerator coefficients, highest order first.
+const INV_NORMAL_A: [f64; 6] = [
+    -3.969_683_028_665_376e1,
+    2.209_460_984_245_205e2,
+    -2.759_285_104_469_687e2,
+    1.383_577_518_672_690e2,
+    -3.066_479_806_614_716e1,
+    2.506_628_277_459_239,
+];
+
+/// Inverse normal cumulative distribution central region denominator coefficients, highest order first.
+const INV_NORMAL_B: [f64; 6] = [
+    -5.447_609_879_822_406e1,
+    1.615_858_368_580_409e2,
+    -1.556_989_798_598_866e2,
+    6.680_131_188_771_972e1,
+    -1.328_068_155_288_572e1,
+    1.0,
+];
+
+/// Inverse normal cumulative distribution tail numerator coefficients, highest order first.
+const INV_NORMAL_C: [f64; 6] = [
+    -7.784_894_002_430_293e-3,
+    -3.223_964_580_411_365e-1,
+    -2.400_758_277_161_838,
+    -2.549_732_539_343_734,
+    4.374_664_141_464_968,
+    2.938_163_982_698_783,
+];
+
+/// Inverse normal cumulative distribution tail denominator coefficients, highest order first.
+const INV_NORMAL_D: [f64; 5] = [
+    7.784_695_709_041_462e-3,
+    3.224_671_290_700_398e-1,
+    2.445_134_137_142_996,
+    3.754_408_661_907_416,
+    1.0,
+];
+
+/// Cumulative probability below which the inverse normal cumulative distribution uses the tail approximation.
+const INV_NORMAL_TAIL: f64 = 0.024_25;
+
+/// Number of Halley refinements applied to the inverse normal cumulative distribution approximation.
+const INV_NORMAL_REFINEMENTS: usize = 2;
 
 /// Sample the Henyey-Greenstein phase function with a given asymmetry parameter.
 #[inline]
 #[must_use]
-pub fn henyey_greenstein(rng: &mut ThreadRng, asym: f64) -> f64 {
+pub fn henyey_greenstein<R: Rng>(rng: &mut R, asym: f64) -> f64 {
     debug_assert!(asym.abs() <= 1.0);
 
     if asym.abs() < 1.0e-6 {
@@ -20,10 +75,24 @@ pub fn henyey_greenstein(rng: &mut ThreadRng, asym: f64) -> f64 {
         .acos()
 }
 
+/// Sample the Rayleigh phase function.
+#[inline]
+#[must_use]
+pub fn rayleigh<R: Rng>(rng: &mut R) -> f64 {
+    // Invert the cumulative distribution mu^3 + 3mu = 8r - 4 with Cardano's method.
+    let b = rng.gen_range(-4.0_f64, 4.0);
+    let d = b.mul_add(b / 4.0, 1.0).sqrt();
+
+    ((b / 2.0 + d).cbrt() + (b / 2.0 - d).cbrt())
+        .max(-1.0)
+        .min(1.0)
+        .acos()
+}
+
 /// Sample the normal distribution.
 #[inline]
 #[must_use]
-pub fn normal(rng: &mut ThreadRng) -> f64 {
+pub fn normal<R: Rng>(rng: &mut R) -> f64 {
     let a = (-2.0 * rng.gen_range(0.0_f64, 1.0).ln()).sqrt();
     let theta = rng.gen_range(0.0, 2.0 * PI);
 
@@ -35,7 +104,7 @@ pub fn normal(rng: &mut ThreadRng) -> f64 {
 /// Sample a gaussian distribution.
 #[inline]
 #[must_use]
-pub fn gaussian(rng: &mut ThreadRng, mu: f64, sigma: f64) -> f64 {
+pub fn gaussian<R: Rng>(rng: &mut R, mu: f64, sigma: f64) -> f64 {
     debug_assert!(sigma > 0.0);
 
     normal(rng).mul_add(sigma, mu)
@@ -44,7 +113,7 @@ pub fn gaussian(rng: &mut ThreadRng, mu: f64, sigma: f64) -> f64 {
 /// Create a random unit vector.
 #[inline]
 #[must_use]
-pub fn isotropic(rng: &mut ThreadRng) -> Dir3 {
+pub fn isotropic<R: Rng>(rng: &mut R) -> Dir3 {
     let theta = rng.gen_range(0.0, 2.0 * PI);
     let z: f64 = rng.gen_range(-1.0, 1.0);
 
@@ -55,3 +124,132 @@ pub fn isotropic(rng: &mut ThreadRng) -> Dir3 {
 
     Dir3::new_normalize(Vec3::new(x, y, z))
 }
+
+/// Integrate a polar angle [rad] tabulated distribution, given per unit solid angle, to form its inverse cumulative distribution function.
+#[inline]
+#[must_use]
+pub fn polar_inv_cdf(thetas: &Array1<f64>, ps: &Array1<f64>) -> Formula {
+    debug_assert!(thetas.len() >= 2);
+    debug_assert!(thetas.len() == ps.len());
+    debug_assert!(ps.iter().all(|p| *p >= 0.0));
+
+    let mut cdf = Vec::with_capacity(thetas.len());
+    let mut xs = Vec::with_capacity(thetas.len());
+    let mut total = 0.0;
+    cdf.push(total);
+    xs.push(thetas[0]);
+    for i in 1..thetas.len() {
+        let (theta_prev, theta_curr) = (thetas[i - 1], thetas[i]);
+        let area = 0.5
+            * (ps[i - 1].mul_add(theta_prev.sin(), ps[i] * theta_curr.sin()))
+            * (theta_curr - theta_prev);
+        if area > 0.0 {
+            total += area;
+            cdf.push(total);
+            xs.push(theta_curr);
+        }
+    }
+    debug_assert!(total > 0.0);
+
+    let mut cdf = Array1::from(cdf);
+    cdf /= total;
+
+    Formula::new_linear_spline_auto(cdf, Array1::from(xs))
+}
+
+/// Evaluate the complementary error function.
+/// A series is used for small arguments, and a continued fraction for large arguments,
+/// so that the relative precision is retained far into the tail.
+#[inline]
+#[must_use]
+pub fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+    if x >= ERFC_MAX {
+        return 0.0;
+    }
+
+    if x < ERFC_SPLIT {
+        let x2 = x * x;
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 0.0;
+        while term > (sum * EPSILON) {
+            n += 1.0;
+            term *= 2.0 * x2 / 2.0_f64.mul_add(n, 1.0);
+            sum += term;
+        }
+
+        return (2.0 / PI.sqrt()).mul_add(-(-x2).exp() * sum, 1.0);
+    }
+
+    // Modified Lentz evaluation of x + (1/2) / (x + 1 / (x + (3/2) / (x + ...))).
+    let mut f = x;
+    let mut c = x;
+    let mut d = 0.0;
+    let mut n = 0.0_f64;
+    loop {
+        n += 1.0;
+        let a = n / 2.0;
+        d = 1.0 / a.mul_add(d, x);
+        c = x + (a / c);
+        let delta = c * d;
+        f *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    (-x * x).exp() / (PI.sqrt() * f)
+}
+
+/// Evaluate the standard normal cumulative distribution function.
+#[inline]
+#[must_use]
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / SQRT_2)
+}
+
+/// Evaluate the inverse of the standard normal cumulative distribution function.
+/// A rational approximation is refined with Halley's method.
+#[inline]
+#[must_use]
+pub fn normal_inv_cdf(p: f64) -> f64 {
+    debug_assert!(p >= 0.0);
+    debug_assert!(p <= 1.0);
+
+    if p <= 0.0 {
+        return NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return INFINITY;
+    }
+
+    let mut z = if p < INV_NORMAL_TAIL {
+        let q = (-2.0 * p.ln()).sqrt();
+        horner(&INV_NORMAL_C, q) / horner(&INV_NORMAL_D, q)
+    } else if p <= (1.0 - INV_NORMAL_TAIL) {
+        let q = p - 0.5;
+        let r = q * q;
+        q * horner(&INV_NORMAL_A, r) / horner(&INV_NORMAL_B, r)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -horner(&INV_NORMAL_C, q) / horner(&INV_NORMAL_D, q)
+    };
+
+    for _ in 0..INV_NORMAL_REFINEMENTS {
+        let e = normal_cdf(z) - p;
+        let u = e * (2.0 * PI).sqrt() * (z * z / 2.0).exp();
+        z -= u / (z * u).mul_add(0.5, 1.0);
+    }
+
+    z
+}
+
+/// Evaluate a polynomial, with coefficients given from highest order first.
+#[inline]
+#[must_use]
+fn horner(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().fold(0.0, |acc, c| acc.mul_add(x, *c))
+}
@@ -3,5 +3,6 @@
 pub mod distribution;
 pub mod perlin_map;
 pub mod probability;
+pub mod stream;
 
-pub use self::{perlin_map::*, probability::*};
+pub use self::{perlin_map::*, probability::*, stream::*};
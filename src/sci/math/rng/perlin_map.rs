@@ -2,7 +2,7 @@
 
 use crate::{display_field, tools::lerp, Dir2, Vec2, X, Y};
 use ndarray::{Array2, Axis};
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::{
     f64::consts::PI,
     fmt::{Display, Formatter, Result},
@@ -20,7 +20,7 @@ impl PerlinMap {
     #[allow(clippy::result_expect_used)]
     #[inline]
     #[must_use]
-    pub fn new(res: [usize; 2], rng: &mut ThreadRng) -> Self {
+    pub fn new<R: Rng>(res: [usize; 2], rng: &mut R) -> Self {
         debug_assert!(res[X] > 0);
         debug_assert!(res[Y] > 0);
 
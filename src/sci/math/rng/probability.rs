@@ -1,8 +1,8 @@
 //! Probability distribution implementation.
 
-use crate::{distribution, Formula};
+use crate::{distribution, Formula, Range};
 use ndarray::Array1;
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::fmt::{Display, Formatter, Result};
 
 /// Probability distribution formulae.
@@ -27,6 +27,10 @@ pub enum Probability {
     },
     /// Linear function.
     Linear {
+        /// Minimum value.
+        min: f64,
+        /// Maximum value.
+        max: f64,
         /// Constant t.
         t: f64,
         /// Offset constant delta.
@@ -45,6 +49,8 @@ pub enum Probability {
     ConstantSpline {
         /// Cumulative distribution function.
         cdf: Formula,
+        /// Inverse cumulative distribution function.
+        inv_cdf: Formula,
     },
 }
 
@@ -86,7 +92,13 @@ impl Probability {
         let delta = (c.powi(2) / grad.powi(2)) + (2.0 * lower / grad);
         let lambda = 2.0 * area / grad;
 
-        Self::Linear { t, delta, lambda }
+        Self::Linear {
+            min,
+            max,
+            t,
+            delta,
+            lambda,
+        }
     }
 
     /// Construct a new gaussian instance.
@@ -117,25 +129,152 @@ impl Probability {
         cdf /= total;
 
         Self::ConstantSpline {
-            cdf: Formula::new_linear_spline_auto(cdf, xs),
+            cdf: Formula::new_linear_spline_auto(xs.clone(), cdf.clone()),
+            inv_cdf: Formula::new_linear_spline_auto(cdf, xs),
         }
     }
 
     /// Generate a random number from the described distribution.
     #[inline]
     #[must_use]
-    pub fn gen(&self, rng: &mut ThreadRng) -> f64 {
+    pub fn gen<R: Rng>(&self, rng: &mut R) -> f64 {
         match self {
             Self::Point { c } => *c,
             Self::Points { cs } => cs[rng.gen_range(0, cs.len())],
             Self::Uniform { min, max } => rng.gen_range(*min, *max),
-            Self::Linear { t, delta, lambda } => {
-                t + (delta - (lambda * rng.gen_range(0.0, 1.0))).sqrt()
-            }
+            Self::Linear { .. } | Self::ConstantSpline { .. } => self.inv_cdf(rng.gen()),
             Self::Gaussian { mu, sigma } => distribution::gaussian(rng, *mu, *sigma),
-            Self::ConstantSpline { cdf } => cdf.y(rng.gen()),
         }
     }
+
+    /// Calculate the fraction of the distribution lying within a range.
+    #[inline]
+    #[must_use]
+    pub fn fraction(&self, range: &Range) -> f64 {
+        match self {
+            Self::Point { c } => {
+                if range.contains(*c) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Points { cs } => {
+                cs.iter().filter(|c| range.contains(**c)).count() as f64 / cs.len() as f64
+            }
+            Self::Gaussian { mu, sigma } => {
+                let (lower, upper, _mirror) = Self::gaussian_bounds(*mu, *sigma, range);
+                upper - lower
+            }
+            Self::Uniform { .. } | Self::Linear { .. } | Self::ConstantSpline { .. } => {
+                (self.cdf(range.max()) - self.cdf(range.min())).max(0.0)
+            }
+        }
+    }
+
+    /// Generate a random number from the described distribution, restricted to lie within a range.
+    /// The distribution is renormalised over the range by inverse transform sampling,
+    /// and the fraction of the full distribution lying within the range is returned alongside the value.
+    /// Returns None if the range excludes the whole distribution.
+    #[inline]
+    #[must_use]
+    pub fn gen_within<R: Rng>(&self, rng: &mut R, range: &Range) -> Option<(f64, f64)> {
+        let frac = self.fraction(range);
+        if frac <= 0.0 {
+            return None;
+        }
+
+        let x = match self {
+            Self::Point { c } => *c,
+            Self::Points { cs } => {
+                let n = rng.gen_range(0, cs.iter().filter(|c| range.contains(**c)).count());
+                *cs.iter()
+                    .filter(|c| range.contains(**c))
+                    .nth(n)
+                    .expect("Could not select point within range.")
+            }
+            Self::Gaussian { mu, sigma } => {
+                let (lower, upper, mirror) = Self::gaussian_bounds(*mu, *sigma, range);
+                let z = distribution::normal_inv_cdf(rng.gen_range(lower, upper));
+                let z = if mirror { -z } else { z };
+                z.mul_add(*sigma, *mu)
+            }
+            Self::Uniform { .. } | Self::Linear { .. } | Self::ConstantSpline { .. } => {
+                let lower = self.cdf(range.min());
+                self.inv_cdf(rng.gen::<f64>().mul_add(frac, lower))
+            }
+        };
+
+        Some((x.max(range.min()).min(range.max()), frac))
+    }
+
+    /// Evaluate the cumulative distribution function of a continuous distribution.
+    #[inline]
+    #[must_use]
+    fn cdf(&self, x: f64) -> f64 {
+        match self {
+            Self::Uniform { min, max } => ((x - min) / (max - min)).max(0.0).min(1.0),
+            Self::Linear {
+                min,
+                max,
+                t,
+                delta,
+                lambda,
+            } => {
+                let x = x.max(*min).min(*max);
+                ((x - t).powi(2) - delta) / lambda
+            }
+            Self::ConstantSpline { cdf, inv_cdf } => {
+                let (first, last) = (inv_cdf.y(0.0), inv_cdf.y(1.0));
+                cdf.y(x.max(first).min(last))
+            }
+            Self::Point { .. } | Self::Points { .. } | Self::Gaussian { .. } => {
+                panic!("Distribution does not have a piecewise cumulative distribution function.")
+            }
+        }
+    }
+
+    /// Evaluate the inverse cumulative distribution function of a continuous distribution.
+    #[inline]
+    #[must_use]
+    fn inv_cdf(&self, u: f64) -> f64 {
+        debug_assert!(u >= 0.0);
+        debug_assert!(u <= 1.0);
+
+        match self {
+            Self::Uniform { min, max } => u.mul_add(max - min, *min),
+            Self::Linear {
+                t, delta, lambda, ..
+            } => t + lambda.mul_add(u, *delta).max(0.0).sqrt().copysign(*lambda),
+            Self::ConstantSpline { inv_cdf, .. } => inv_cdf.y(u),
+            Self::Point { .. } | Self::Points { .. } | Self::Gaussian { .. } => {
+                panic!("Distribution does not have a piecewise cumulative distribution function.")
+            }
+        }
+    }
+
+    /// Determine the standard normal cumulative probabilities bounding a range of a gaussian distribution.
+    /// Ranges lying above the mean are mirrored below it, so that the probabilities of tails retain their precision.
+    /// Returns the lower and upper probabilities, and whether the range was mirrored.
+    #[inline]
+    #[must_use]
+    fn gaussian_bounds(mu: f64, sigma: f64, range: &Range) -> (f64, f64, bool) {
+        let z_min = (range.min() - mu) / sigma;
+        let z_max = (range.max() - mu) / sigma;
+
+        let mirror = (z_min + z_max) > 0.0;
+        let (z_lower, z_upper) = if mirror {
+            (-z_max, -z_min)
+        } else {
+            (z_min, z_max)
+        };
+
+        (
+            distribution::normal_cdf(z_lower),
+            distribution::normal_cdf(z_upper),
+            mirror,
+        )
+    }
 }
 
 impl Display for Probability {
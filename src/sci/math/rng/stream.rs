@@ -0,0 +1,42 @@
+//! Seedable random number stream functions.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Seedable pseudo-random number generator.
+pub type Prng = ChaCha8Rng;
+
+/// Construct a generator for an independent stream derived from a master seed.
+/// Generators sharing a seed, but with differing stream ids, produce uncorrelated sequences.
+#[inline]
+#[must_use]
+pub fn stream(seed: u64, id: u64) -> Prng {
+    let mut rng = Prng::seed_from_u64(seed);
+    rng.set_stream(id);
+    rng
+}
+
+/// Construct a generator for a stream derived from a master seed, resuming from a given word position.
+#[inline]
+#[must_use]
+pub fn resume_stream(seed: u64, id: u64, word_pos: u128) -> Prng {
+    let mut rng = stream(seed, id);
+    rng.set_word_pos(word_pos);
+    rng
+}
+
+/// Generate a uniform value in [0, 1) from a hash of a master seed, stream id and index.
+/// Unlike drawing from a generator, this consumes no stream state.
+#[inline]
+#[must_use]
+pub fn hash_unit(seed: u64, id: u64, index: u64) -> f64 {
+    let mut x = seed ^ id.rotate_left(21) ^ index.rotate_left(42);
+    for _ in 0..2 {
+        x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^= x >> 31;
+    }
+
+    (x >> 11) as f64 / (1_u64 << 53) as f64
+}
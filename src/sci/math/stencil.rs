@@ -0,0 +1,129 @@
+//! Seven-point finite-volume stencil implementation.
+
+use crate::{X, Y, Z};
+use ndarray::Array3;
+
+/// Seven-point finite-volume stencil on a regular grid.
+/// Each voxel couples to its face neighbours with symmetric conductances,
+/// and its diagonal totals those conductances with any additional sinks.
+pub struct Stencil {
+    /// Conductance, per unit volume, between each voxel and its successor along each axis.
+    conds: [Array3<f64>; 3],
+    /// Total conductance, per unit volume, of each voxel including additional sinks.
+    diags: Array3<f64>,
+}
+
+impl Stencil {
+    /// Construct a new instance, without face conductances, from the sink of each voxel.
+    #[inline]
+    #[must_use]
+    pub fn new(sinks: Array3<f64>) -> Self {
+        let res = sinks.raw_dim();
+
+        Self {
+            conds: [
+                Array3::zeros(res.clone()),
+                Array3::zeros(res.clone()),
+                Array3::zeros(res),
+            ],
+            diags: sinks,
+        }
+    }
+
+    /// Reference the total conductance of each voxel.
+    #[inline]
+    #[must_use]
+    pub const fn diags(&self) -> &Array3<f64> {
+        &self.diags
+    }
+
+    /// Get the resolution of the stencil.
+    #[inline]
+    #[must_use]
+    pub fn res(&self) -> [usize; 3] {
+        let shape = self.diags.shape();
+        [shape[X], shape[Y], shape[Z]]
+    }
+
+    /// Add a sink, such as a boundary loss, to the diagonal of a voxel.
+    #[inline]
+    pub fn add_sink(&mut self, index: [usize; 3], sink: f64) {
+        self.diags[index] += sink;
+    }
+
+    /// Couple a voxel to its successor along an axis, through the face between them,
+    /// given the coefficient of each voxel and the voxel width along the axis.
+    /// The face uses the harmonic mean coefficient of the neighbouring voxels.
+    #[inline]
+    pub fn connect(
+        &mut self,
+        index: [usize; 3],
+        axis: usize,
+        coeff: f64,
+        next_coeff: f64,
+        dx: f64,
+    ) {
+        debug_assert!(index[axis] + 1 < self.res()[axis]);
+        debug_assert!(dx > 0.0);
+
+        let mut next = index;
+        next[axis] += 1;
+
+        let c = (2.0 * coeff * next_coeff) / ((coeff + next_coeff) * dx * dx);
+        self.conds[axis][index] = c;
+        self.diags[index] += c;
+        self.diags[next] += c;
+    }
+
+    /// Sum the conductance weighted values of the neighbours of a voxel.
+    #[inline]
+    #[must_use]
+    pub fn neighbours(&self, values: &Array3<f64>, index: [usize; 3]) -> f64 {
+        let res = self.res();
+
+        let mut sum = 0.0;
+        for (axis, cond) in self.conds.iter().enumerate() {
+            if index[axis] > 0 {
+                let mut prev = index;
+                prev[axis] -= 1;
+                sum += cond[prev] * values[prev];
+            }
+            if index[axis] + 1 < res[axis] {
+                let mut next = index;
+                next[axis] += 1;
+                sum += cond[index] * values[next];
+            }
+        }
+
+        sum
+    }
+
+    /// Perform a single Gauss-Seidel sweep over the voxels, updating each in turn from the latest values of its neighbours.
+    /// The update is given the voxel index, its current value, and the conductance weighted sum of its neighbours,
+    /// and returns the new value, or none to leave the voxel unchanged.
+    /// Returns the largest absolute change.
+    #[inline]
+    pub fn sweep<F>(&self, values: &mut Array3<f64>, mut update: F) -> f64
+    where
+        F: FnMut([usize; 3], f64, f64) -> Option<f64>,
+    {
+        let res = self.res();
+
+        let mut max_change: f64 = 0.0;
+        for xi in 0..res[X] {
+            for yi in 0..res[Y] {
+                for zi in 0..res[Z] {
+                    let index = [xi, yi, zi];
+                    if let Some(value) =
+                        update(index, values[index], self.neighbours(values, index))
+                    {
+                        max_change = max_change.max((value - values[index]).abs());
+                        values[index] = value;
+                    }
+                }
+            }
+        }
+
+        max_change
+    }
+}
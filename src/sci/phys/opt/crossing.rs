@@ -1,6 +1,6 @@
 //! Crossing implementation.
 
-use crate::{access, clone, Dir3};
+use crate::{access, clone, Dir3, Mueller};
 
 /// Crossing structure implementation.
 /// Optical interface Crossing information structure.
@@ -11,12 +11,18 @@ pub struct Crossing {
     ref_dir: Dir3,
     /// Transmission (refraction) direction.
     trans_dir: Option<Dir3>,
+    /// Reflection Mueller matrix, within the plane of incidence frame.
+    ref_mueller: Mueller,
+    /// Transmission Mueller matrix, within the plane of incidence frame.
+    trans_mueller: Option<Mueller>,
 }
 
 impl Crossing {
     clone!(ref_prob, f64);
     access!(ref_dir, Dir3);
     access!(trans_dir, Option<Dir3>);
+    access!(ref_mueller, Mueller);
+    access!(trans_mueller, Option<Mueller>);
 
     /// Construct a new instance.
     #[inline]
@@ -35,41 +41,74 @@ impl Crossing {
             Some((n_next / n_curr).asin())
         };
 
-        let (ref_prob, trans_dir) = if crit_ang.is_some() && (ci.acos() >= crit_ang.unwrap()) {
-            (1.0, None)
-        } else {
-            let s2t = n.powi(2) * (1.0 - ci.powi(2));
-            let ct = (1.0 - s2t).sqrt();
-
-            (
-                Self::init_ref_prob(n_curr, n_next, ci, ct),
-                Some(Self::init_trans_dir(inc, norm, n, ci, ct)),
-            )
-        };
+        let (ref_mueller, trans_mueller, trans_dir) =
+            if crit_ang.is_some() && (ci.acos() >= crit_ang.unwrap()) {
+                (Self::init_total_ref_mueller(n_curr, n_next, ci), None, None)
+            } else {
+                let s2t = n.powi(2) * (1.0 - ci.powi(2));
+                let ct = (1.0 - s2t).sqrt();
+                let (ref_mueller, trans_mueller) = Self::init_muellers(n_curr, n_next, ci, ct);
+
+                (
+                    ref_mueller,
+                    Some(trans_mueller),
+                    Some(Self::init_trans_dir(inc, norm, n, ci, ct)),
+                )
+            };
 
         Self {
-            ref_prob,
+            ref_prob: ref_mueller.s11(),
             ref_dir: Self::init_ref_dir(inc, norm, ci),
             trans_dir,
+            ref_mueller,
+            trans_mueller,
         }
     }
 
-    /// Calculate the reflection probability.
+    /// Calculate the reflection and transmission Mueller matrices from the s- and p-polarised Fresnel coefficients.
+    /// The unpolarised reflection probability is the average of the s- and p-polarised reflectances.
     #[inline]
     #[must_use]
-    fn init_ref_prob(n1: f64, n2: f64, ci: f64, ct: f64) -> f64 {
+    fn init_muellers(n1: f64, n2: f64, ci: f64, ct: f64) -> (Mueller, Mueller) {
         debug_assert!(n1 >= 0.0);
         debug_assert!(n2 >= 0.0);
 
         let n1_c_i = n1 * ci;
         let n2_c_t = n2 * ct;
-        let rn = ((n1_c_i - n2_c_t) / (n1_c_i + n2_c_t)).powi(2);
+        let rs = (n1_c_i - n2_c_t) / (n1_c_i + n2_c_t);
 
         let n2_c_i = n2 * ci;
         let n1_c_t = n1 * ct;
-        let rt = ((n2_c_i - n1_c_t) / (n2_c_i + n1_c_t)).powi(2);
+        let rp = (n2_c_i - n1_c_t) / (n2_c_i + n1_c_t);
+
+        let (ref_s, ref_p) = (rs.powi(2), rp.powi(2));
+        let (trans_s, trans_p) = (1.0 - ref_s, 1.0 - ref_p);
+
+        (
+            Mueller::new((ref_s + ref_p) / 2.0, (ref_p - ref_s) / 2.0, rs * rp, 0.0),
+            Mueller::new(
+                (trans_s + trans_p) / 2.0,
+                (trans_p - trans_s) / 2.0,
+                (trans_s * trans_p).sqrt(),
+                0.0,
+            ),
+        )
+    }
+
+    /// Calculate the total internal reflection Mueller matrix.
+    /// The s- and p-polarised components are fully reflected with a relative phase retardance.
+    #[inline]
+    #[must_use]
+    fn init_total_ref_mueller(n1: f64, n2: f64, ci: f64) -> Mueller {
+        debug_assert!(n1 > n2);
+
+        let n_sq = (n2 / n1).powi(2);
+        let a = ((1.0 - ci.powi(2)) - n_sq).max(0.0).sqrt();
+        let phase_s = 2.0 * (a / ci).atan();
+        let phase_p = 2.0 * (a / (n_sq * ci)).atan();
+        let (sin_delta, cos_delta) = (phase_p - phase_s).sin_cos();
 
-        (rn + rt) / 2.0
+        Mueller::new(1.0, 0.0, cos_delta, sin_delta)
     }
 
     /// Calculate the reflection direction.
@@ -0,0 +1,176 @@
+//! Microfacet roughness implementation.
+
+use crate::{distribution, Dir3, Vec3};
+use attr::load;
+use rand::Rng;
+use std::{
+    f64::consts::{PI, SQRT_2},
+    fmt::{Display, Formatter, Result},
+};
+
+/// Number of bisections used to invert the Beckmann visible slope distribution.
+const BISECTIONS: usize = 64;
+/// Slope below which the Beckmann visible slope distribution is treated as empty.
+const MIN_SLOPE: f64 = -6.0;
+/// Tangential magnitude below which a direction is treated as normal to the surface.
+const NORMAL_TOLERANCE: f64 = 1.0e-9;
+
+/// Microfacet roughness enumeration.
+/// Describes the distribution of microscopic surface normals about the macroscopic normal.
+#[load]
+pub enum Microfacet {
+    /// Beckmann (Gaussian slope) distribution.
+    Beckmann {
+        /// Root-mean-square slope.
+        alpha: f64,
+    },
+    /// Trowbridge-Reitz (GGX) distribution.
+    Ggx {
+        /// Width parameter.
+        alpha: f64,
+    },
+}
+
+impl Microfacet {
+    /// Get the roughness parameter of the distribution.
+    #[inline]
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        match *self {
+            Self::Beckmann { alpha } | Self::Ggx { alpha } => alpha,
+        }
+    }
+
+    /// Sample a microfacet normal from those visible to an incident direction of travel,
+    /// weighted by their projected area, upon a surface with the given macroscopic normal.
+    /// Incident shadowing is accounted for by the visible normal distribution.
+    /// Zero roughness returns the macroscopic normal.
+    #[inline]
+    #[must_use]
+    pub fn sample_normal<R: Rng>(&self, rng: &mut R, inc: &Dir3, norm: &Dir3) -> Dir3 {
+        debug_assert!(inc.dot(norm) < 0.0);
+
+        let alpha = self.alpha();
+        if alpha <= 0.0 {
+            return *norm;
+        }
+
+        // Local frame, with the incoming direction lying in the first tangent-normal plane.
+        let cos_theta = -inc.dot(norm);
+        let tangent = -inc.as_ref() - (norm.as_ref() * cos_theta);
+        let t1 = if tangent.magnitude() > NORMAL_TOLERANCE {
+            Dir3::new_normalize(tangent)
+        } else if norm.z.abs() < 0.9 {
+            Dir3::new_normalize(Vec3::z().cross(norm))
+        } else {
+            Dir3::new_normalize(Vec3::x().cross(norm))
+        };
+        let t2 = norm.cross(&t1);
+        let sin_theta = tangent.magnitude().min(1.0);
+
+        let (x, y, z) = match *self {
+            Self::Beckmann { .. } => {
+                let (sx, sy) = beckmann_slopes(rng, cos_theta, alpha * sin_theta);
+                (-alpha * sx, -alpha * sy, 1.0)
+            }
+            Self::Ggx { .. } => ggx_normal(rng, cos_theta, sin_theta, alpha),
+        };
+
+        Dir3::new_normalize((t1.as_ref() * x) + (t2 * y) + (norm.as_ref() * z))
+    }
+
+    /// Calculate the Smith masking probability: that a direction leaving a surface,
+    /// of the given macroscopic normal, is not blocked by neighbouring microfacets.
+    /// Zero roughness is never masked.
+    #[inline]
+    #[must_use]
+    pub fn masking(&self, dir: &Dir3, norm: &Dir3) -> f64 {
+        let alpha = self.alpha();
+        let cos_theta = dir.dot(norm).abs().min(1.0);
+        let sin_theta = (1.0 - cos_theta.powi(2)).sqrt();
+        if alpha <= 0.0 || sin_theta <= 0.0 {
+            return 1.0;
+        }
+
+        let a = cos_theta / (alpha * sin_theta);
+        let lambda = match *self {
+            Self::Beckmann { .. } => {
+                0.5 * (((-a * a).exp() / (a * PI.sqrt())) - distribution::erfc(a))
+            }
+            Self::Ggx { .. } => 0.5 * ((1.0 + (1.0 / (a * a))).sqrt() - 1.0),
+        };
+
+        1.0 / (1.0 + lambda)
+    }
+}
+
+/// Sample the slopes of a unit roughness Beckmann surface visible from an incident direction,
+/// given the cosine and stretched sine of its angle to the normal.
+/// The first slope lies within the plane of incidence.
+#[inline]
+#[must_use]
+fn beckmann_slopes<R: Rng>(rng: &mut R, cos_theta: f64, sin_theta: f64) -> (f64, f64) {
+    let y = distribution::normal(rng) / SQRT_2;
+    if sin_theta <= NORMAL_TOLERANCE * cos_theta {
+        let x = distribution::normal(rng) / SQRT_2;
+        return (x, y);
+    }
+
+    // Visible slopes are distributed as (cot - x) exp(-x^2), below the cotangent.
+    let cot = cos_theta / sin_theta;
+    let cdf =
+        |x: f64| (cot * PI.sqrt() / 2.0).mul_add(distribution::erfc(-x), 0.5 * (-x * x).exp());
+    let target = rng.gen_range(0.0, cdf(cot));
+
+    let mut lo = MIN_SLOPE.min(cot);
+    let mut hi = cot;
+    for _ in 0..BISECTIONS {
+        let mid = 0.5 * (lo + hi);
+        if cdf(mid) < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    (0.5 * (lo + hi), y)
+}
+
+/// Sample a GGX microfacet normal visible from an incident direction,
+/// given the cosine and sine of its angle to the normal, as local components.
+/// The first component lies within the plane of incidence.
+#[inline]
+#[must_use]
+fn ggx_normal<R: Rng>(rng: &mut R, cos_theta: f64, sin_theta: f64, alpha: f64) -> (f64, f64, f64) {
+    // Stretch the incident direction to the unit roughness configuration.
+    let view = Vec3::new(alpha * sin_theta, 0.0, cos_theta).normalize();
+    let t1 = if view.x > NORMAL_TOLERANCE {
+        Vec3::y()
+    } else {
+        Vec3::x()
+    };
+    let t2 = view.cross(&t1);
+
+    // Sample the projected hemisphere.
+    let r = rng.gen_range(0.0, 1.0_f64).sqrt();
+    let phi = rng.gen_range(0.0, 2.0 * PI);
+    let p1 = r * phi.cos();
+    let s = 0.5 * (1.0 + view.z);
+    let p2 = (1.0 - s).mul_add((1.0 - p1.powi(2)).sqrt(), s * r * phi.sin());
+    let p3 = (1.0 - p1.powi(2) - p2.powi(2)).max(0.0).sqrt();
+    let hemi = (t1 * p1) + (t2 * p2) + (view * p3);
+
+    // Unstretch.
+    (alpha * hemi.x, alpha * hemi.y, hemi.z.max(0.0))
+}
+
+impl Display for Microfacet {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match *self {
+            Self::Beckmann { alpha } => write!(fmt, "Beckmann (alpha: {})", alpha),
+            Self::Ggx { alpha } => write!(fmt, "GGX (alpha: {})", alpha),
+        }
+    }
+}
@@ -1,5 +1,9 @@
 //! Optics module.
 
 pub mod crossing;
+pub mod microfacet;
+pub mod mueller;
+pub mod profile;
+pub mod stokes;
 
-pub use self::crossing::*;
+pub use self::{crossing::*, microfacet::*, mueller::*, profile::*, stokes::*};
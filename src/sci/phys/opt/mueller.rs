@@ -0,0 +1,71 @@
+//! Mueller matrix implementation.
+
+use crate::{clone, Stokes};
+
+/// Mueller matrix of a symmetric interaction.
+/// Takes the block-diagonal form:
+/// | s11 s12   0   0 |
+/// | s12 s11   0   0 |
+/// |   0   0 s33 s34 |
+/// |   0   0-s34 s33 |
+#[derive(Clone)]
+pub struct Mueller {
+    /// Intensity term.
+    s11: f64,
+    /// Linear polarisation coupling term.
+    s12: f64,
+    /// Linear retention term.
+    s33: f64,
+    /// Retardance term.
+    s34: f64,
+}
+
+impl Mueller {
+    clone!(s11, f64);
+    clone!(s12, f64);
+    clone!(s33, f64);
+    clone!(s34, f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(s11: f64, s12: f64, s33: f64, s34: f64) -> Self {
+        debug_assert!(s11 >= 0.0);
+        debug_assert!(s12.abs() <= s11 * (1.0 + 1.0e-9));
+
+        Self { s11, s12, s33, s34 }
+    }
+
+    /// Construct the unit intensity Rayleigh scattering matrix for a given scattering angle cosine.
+    #[inline]
+    #[must_use]
+    pub fn new_rayleigh(cos_theta: f64) -> Self {
+        debug_assert!(cos_theta.abs() <= 1.0);
+
+        let cos_sq = cos_theta * cos_theta;
+        let norm = 1.0 + cos_sq;
+
+        Self::new(1.0, (cos_sq - 1.0) / norm, (2.0 * cos_theta) / norm, 0.0)
+    }
+
+    /// Determine the outgoing intensity of a given Stokes vector.
+    #[inline]
+    #[must_use]
+    pub fn intensity(&self, stokes: &Stokes) -> f64 {
+        self.s11.mul_add(stokes.i(), self.s12 * stokes.q())
+    }
+
+    /// Apply the matrix to a Stokes vector.
+    #[inline]
+    #[must_use]
+    pub fn apply(&self, stokes: &Stokes) -> Stokes {
+        let (i, q, u, v) = (stokes.i(), stokes.q(), stokes.u(), stokes.v());
+
+        Stokes::new(
+            self.s11.mul_add(i, self.s12 * q),
+            self.s12.mul_add(i, self.s11 * q),
+            self.s33.mul_add(u, self.s34 * v),
+            (-self.s34).mul_add(u, self.s33 * v),
+        )
+    }
+}
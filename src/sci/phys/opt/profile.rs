@@ -0,0 +1,77 @@
+//! Angular emission profile implementation.
+
+use crate::{distribution, Formula};
+use ndarray::Array1;
+use rand::Rng;
+use std::fmt::{Display, Formatter, Result};
+
+/// Angular emission profile enumeration, describing emission about a surface normal.
+pub enum Profile {
+    /// Emission exactly along the normal.
+    Collimated,
+    /// Cosine-weighted emission over the hemisphere.
+    Lambertian,
+    /// Uniform emission over the hemisphere.
+    Hemisphere,
+    /// Uniform emission within a cone.
+    Cone {
+        /// Half-angle [rad].
+        half_angle: f64,
+    },
+    /// Tabulated polar intensity curve.
+    Tabulated {
+        /// Inverse cumulative distribution function, mapping a uniform variate to a polar angle [rad].
+        inv_cdf: Formula,
+    },
+}
+
+impl Profile {
+    /// Construct a new cone instance.
+    #[inline]
+    #[must_use]
+    pub fn new_cone(half_angle: f64) -> Self {
+        debug_assert!(half_angle > 0.0);
+        debug_assert!(half_angle <= std::f64::consts::PI);
+
+        Self::Cone { half_angle }
+    }
+
+    /// Construct a new tabulated instance from the intensity per unit solid angle at each polar angle [rad].
+    #[inline]
+    #[must_use]
+    pub fn new_tabulated(thetas: &Array1<f64>, intensities: &Array1<f64>) -> Self {
+        Self::Tabulated {
+            inv_cdf: distribution::polar_inv_cdf(thetas, intensities),
+        }
+    }
+
+    /// Sample a polar angle [rad] from the normal.
+    #[inline]
+    #[must_use]
+    pub fn gen<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            Self::Collimated => 0.0,
+            Self::Lambertian => rng.gen_range(0.0_f64, 1.0).sqrt().acos(),
+            Self::Hemisphere => rng.gen_range(0.0_f64, 1.0).acos(),
+            Self::Cone { half_angle } => {
+                let min_cos = half_angle.cos();
+                rng.gen_range(min_cos, 1.0).acos()
+            }
+            Self::Tabulated { inv_cdf } => inv_cdf.y(rng.gen()),
+        }
+    }
+}
+
+impl Display for Profile {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Collimated => write!(fmt, "Collimated"),
+            Self::Lambertian => write!(fmt, "Lambertian"),
+            Self::Hemisphere => write!(fmt, "Hemisphere"),
+            Self::Cone { half_angle } => write!(fmt, "Cone ({} deg)", half_angle.to_degrees()),
+            Self::Tabulated { .. } => write!(fmt, "Tabulated"),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Stokes vector implementation.
+
+use crate::{clone, display_field, display_field_ln, Error, Save};
+use attr::load;
+use serde::Serialize;
+use std::{
+    fmt::{Display, Formatter, Result},
+    fs::File,
+    io::Write,
+    ops::{AddAssign, Mul},
+    path::Path,
+};
+
+/// Stokes polarisation vector.
+/// Q is positive for light polarised parallel to the reference frame.
+#[load]
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub struct Stokes {
+    /// Total intensity.
+    i: f64,
+    /// Linear polarisation parallel (+) or perpendicular (-) to the reference frame.
+    q: f64,
+    /// Linear polarisation at +45 (+) or -45 (-) degrees to the reference frame.
+    u: f64,
+    /// Right (+) or left (-) circular polarisation.
+    v: f64,
+}
+
+impl Stokes {
+    clone!(i, f64);
+    clone!(q, f64);
+    clone!(u, f64);
+    clone!(v, f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(i: f64, q: f64, u: f64, v: f64) -> Self {
+        debug_assert!(i >= 0.0);
+        debug_assert!(q.mul_add(q, u.mul_add(u, v * v)) <= (i * i) * (1.0 + 1.0e-9));
+
+        Self { i, q, u, v }
+    }
+
+    /// Construct a unit intensity unpolarised instance.
+    #[inline]
+    #[must_use]
+    pub const fn new_unpolarised() -> Self {
+        Self {
+            i: 1.0,
+            q: 0.0,
+            u: 0.0,
+            v: 0.0,
+        }
+    }
+
+    /// Construct a zero intensity instance.
+    #[inline]
+    #[must_use]
+    pub const fn new_zero() -> Self {
+        Self {
+            i: 0.0,
+            q: 0.0,
+            u: 0.0,
+            v: 0.0,
+        }
+    }
+
+    /// Calculate the degree of polarisation.
+    #[inline]
+    #[must_use]
+    pub fn degree(&self) -> f64 {
+        if self.i <= 0.0 {
+            return 0.0;
+        }
+
+        self.q
+            .mul_add(self.q, self.u.mul_add(self.u, self.v * self.v))
+            .sqrt()
+            / self.i
+    }
+
+    /// Rotate the reference frame anticlockwise, looking into the direction of travel, by the given angle [rad].
+    #[inline]
+    pub fn rotate(&mut self, angle: f64) {
+        let (s, c) = (2.0 * angle).sin_cos();
+        let (q, u) = (self.q, self.u);
+
+        self.q = q.mul_add(c, u * s);
+        self.u = (-q).mul_add(s, u * c);
+    }
+
+    /// Scale to unit intensity.
+    #[inline]
+    pub fn normalise(&mut self) {
+        debug_assert!(self.i > 0.0);
+
+        let inv = 1.0 / self.i;
+        self.i = 1.0;
+        self.q *= inv;
+        self.u *= inv;
+        self.v *= inv;
+    }
+}
+
+impl Mul<f64> for Stokes {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f64) -> Self {
+        Self {
+            i: self.i * rhs,
+            q: self.q * rhs,
+            u: self.u * rhs,
+            v: self.v * rhs,
+        }
+    }
+}
+
+impl AddAssign<&Self> for Stokes {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.i += rhs.i;
+        self.q += rhs.q;
+        self.u += rhs.u;
+        self.v += rhs.v;
+    }
+}
+
+impl Save for Stokes {
+    #[inline]
+    fn save(&self, path: &Path) -> std::result::Result<(), Error> {
+        let mut file = File::create(path)?;
+
+        writeln!(file, "I, Q, U, V, DOP")?;
+        writeln!(
+            file,
+            "{}, {}, {}, {}, {}",
+            self.i,
+            self.q,
+            self.u,
+            self.v,
+            self.degree()
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Display for Stokes {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "I", self.i)?;
+        display_field_ln!(fmt, "Q", self.q)?;
+        display_field_ln!(fmt, "U", self.u)?;
+        display_field!(fmt, "V", self.v)
+    }
+}
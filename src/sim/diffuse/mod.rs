@@ -0,0 +1,8 @@
+//! Diffusion approximation module.
+
+pub mod output;
+pub mod settings;
+pub mod source;
+pub mod system;
+
+pub use self::{output::*, settings::*, source::*, system::*};
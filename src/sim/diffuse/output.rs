@@ -0,0 +1,117 @@
+//! Output data structure.
+
+use crate::{display_field, display_field_ln, put_variable, put_voxel_centres, Aabb, Error, Save};
+use ndarray::Array3;
+use physical_constants::SPEED_OF_LIGHT_IN_VACUUM;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Diffusion output data structure.
+pub struct Output {
+    /// Measured volume.
+    boundary: Aabb,
+    /// Cell volume [m^3].
+    cell_vol: f64,
+    /// Fluence rate [W/m^2].
+    pub fluence_rate: Array3<f64>,
+    /// Light energy density [J/m^3].
+    pub energy_dens: Array3<f64>,
+    /// Absorbed power density [W/m^3].
+    pub absorption_dens: Array3<f64>,
+}
+
+impl Output {
+    /// Construct a new instance from the fluence rate [W/m^2] within a measured volume,
+    /// and the refractive index and absorption coefficient [1/m] of each voxel.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        boundary: Aabb,
+        cell_vol: f64,
+        fluence_rate: Array3<f64>,
+        ref_index: &Array3<f64>,
+        abs_coeff: &Array3<f64>,
+    ) -> Self {
+        debug_assert!(cell_vol > 0.0);
+
+        let energy_dens = &fluence_rate * ref_index / SPEED_OF_LIGHT_IN_VACUUM;
+        let absorption_dens = &fluence_rate * abs_coeff;
+
+        Self {
+            boundary,
+            cell_vol,
+            fluence_rate,
+            energy_dens,
+            absorption_dens,
+        }
+    }
+
+    /// Calculate the total absorbed power [W].
+    #[inline]
+    #[must_use]
+    pub fn absorbed_power(&self) -> f64 {
+        self.absorption_dens.sum() * self.cell_vol
+    }
+}
+
+impl Save for Output {
+    /// Save the voxel data within a single NetCDF file,
+    /// following the layout and variable names of the MCRT output.
+    #[inline]
+    fn save(&self, out_dir: &Path) -> Result<(), Error> {
+        let path = out_dir.join("diffuse.nc");
+        println!("saving: {}", path.display());
+        let mut file = netcdf::create(&path)?;
+
+        file.add_attribute("title", "Diffusion output")?;
+        file.add_attribute("cell_volume", self.cell_vol)?;
+
+        let dims = ["x", "y", "z"];
+        put_voxel_centres(&mut file, &self.boundary, self.fluence_rate.shape())?;
+
+        for (name, dens, units, long_name) in &[
+            ("fluence_rate", &self.fluence_rate, "W m^-2", "fluence rate"),
+            (
+                "energy_dens",
+                &self.energy_dens,
+                "J m^-3",
+                "light energy density",
+            ),
+            (
+                "absorption_dens",
+                &self.absorption_dens,
+                "W m^-3",
+                "absorbed power density",
+            ),
+        ] {
+            put_variable(
+                &mut file,
+                name,
+                &dims,
+                dens.as_slice().ok_or("Missing slice data.")?,
+                units,
+                long_name,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Output {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(
+            fmt,
+            "max fluence rate",
+            self.fluence_rate
+                .iter()
+                .fold(0.0, |max: f64, f| max.max(*f)),
+            "W/m^2"
+        )?;
+        display_field!(fmt, "absorbed power", self.absorbed_power(), "W")
+    }
+}
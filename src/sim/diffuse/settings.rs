@@ -0,0 +1,62 @@
+//! Settings implementation.
+
+use crate::{access, clone, display_field, display_field_ln, Group};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Diffusion settings structure.
+#[load]
+pub struct Settings {
+    /// Wavelength [m] at which optical properties are evaluated.
+    wavelength: f64,
+    /// Reduced scattering coefficient [1/m] below which voxels are not diffusive.
+    min_reduced_scat: f64,
+    /// Number of emission samples used to construct the source.
+    source_samples: u64,
+    /// Source sampling random number seed.
+    seed: u64,
+    /// Bump distance [m].
+    bump_dist: f64,
+    /// Initial material.
+    init_mat: Group,
+    /// Maximum fluence rate change, relative to the maximum fluence rate, of the final iteration.
+    tolerance: f64,
+    /// Maximum number of iterations.
+    max_iters: u64,
+    /// Optional successive over-relaxation factor, defaulting to unity (Gauss-Seidel).
+    relaxation: Option<f64>,
+}
+
+impl Settings {
+    clone!(wavelength, f64);
+    clone!(min_reduced_scat, f64);
+    clone!(source_samples, u64);
+    clone!(seed, u64);
+    clone!(bump_dist, f64);
+    access!(init_mat, Group);
+    clone!(tolerance, f64);
+    clone!(max_iters, u64);
+
+    /// Get the over-relaxation factor.
+    #[inline]
+    #[must_use]
+    pub fn relaxation(&self) -> f64 {
+        self.relaxation.unwrap_or(1.0)
+    }
+}
+
+impl Display for Settings {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "wavelength", self.wavelength, "m")?;
+        display_field_ln!(fmt, "min reduced scattering", self.min_reduced_scat, "m^-1")?;
+        display_field_ln!(fmt, "source samples", self.source_samples)?;
+        display_field_ln!(fmt, "seed", self.seed)?;
+        display_field_ln!(fmt, "bump distance", self.bump_dist, "m")?;
+        display_field_ln!(fmt, "initial material", &self.init_mat)?;
+        display_field_ln!(fmt, "tolerance", self.tolerance)?;
+        display_field_ln!(fmt, "max iterations", self.max_iters)?;
+        display_field!(fmt, "relaxation", self.relaxation())
+    }
+}
@@ -0,0 +1,99 @@
+//! Diffusion source construction.
+
+use crate::{
+    diffuse::{reduced_scat, Settings, System},
+    grid::Grid,
+    mcrt::{resolve_material, select_light, total_power, Interface, Light, Material},
+    stream,
+    tree::Cell,
+    Crossing, Range, Set,
+};
+use ndarray::Array3;
+
+/// Construct the isotropic source power density [W/m^3] equivalent to a set of lights.
+/// Emission is traced through non-diffusive materials, losing the Fresnel reflected fraction at each crossing,
+/// and deposited one reduced mean free path within the first diffusive material it enters.
+/// Emission which leaves the grid, or is deposited within a non-diffusive voxel, is lost.
+#[inline]
+#[must_use]
+pub fn illuminate(
+    sett: &Settings,
+    sys: &System,
+    lights: &Set<Light>,
+    mats: &Set<Material>,
+    inters: &Set<Interface>,
+    tree: &Cell,
+    grid: &Grid,
+) -> Array3<f64> {
+    let w = sett.wavelength();
+    let bump_dist = sett.bump_dist();
+    let samples = sett.source_samples();
+    let phot_power = total_power(lights) / samples as f64;
+    let cell_vol = grid.voxel_vol();
+
+    let mut rng = stream(sett.seed(), 0);
+    let mut sources = Array3::zeros(sys.res());
+    for _ in 0..samples {
+        let (_index, light) = select_light(lights, &mut rng);
+        let phot = light.emit(
+            phot_power,
+            &Range::new_infinite(),
+            grid.boundary(),
+            &mut rng,
+        );
+        let mut power = phot.weight() * phot.power();
+        let mut ray = phot.ray().clone();
+
+        let group =
+            resolve_material(tree, inters, &ray, bump_dist).unwrap_or_else(|| sett.init_mat());
+        let mut mat = &mats.map()[group];
+
+        loop {
+            // Deposit within the first diffusive material.
+            if let Some(index) = grid.gen_index(ray.pos()) {
+                let env = mat.voxel_env(w, index);
+                if reduced_scat(&env) >= sett.min_reduced_scat() {
+                    ray.travel(sys.mean_free_path(index));
+                    if let Some(index) = grid.gen_index(ray.pos()) {
+                        if sys.is_diffusive(index) {
+                            sources[index] += power / cell_vol;
+                        }
+                    }
+                    break;
+                }
+            }
+
+            // Otherwise continue to the next interface.
+            let hit = if let Some(hit) = tree.observe(ray.clone(), bump_dist, std::f64::INFINITY) {
+                hit
+            } else {
+                break;
+            };
+            let inter = if let Some(inter) = inters.map().get(hit.group()) {
+                inter
+            } else {
+                ray.travel(hit.dist() + bump_dist);
+                continue;
+            };
+
+            let next_mat = &mats.map()[inter.next(hit.side())];
+            ray.travel(hit.dist());
+            let crossing = Crossing::new(
+                ray.dir(),
+                hit.side().norm(),
+                mat.env(w).ref_index(),
+                next_mat.env(w).ref_index(),
+            );
+            if let Some(trans_dir) = crossing.trans_dir() {
+                power *= 1.0 - crossing.ref_prob();
+                *ray.dir_mut() = *trans_dir;
+            } else {
+                break;
+            }
+            ray.travel(bump_dist);
+            mat = next_mat;
+        }
+    }
+
+    sources
+}
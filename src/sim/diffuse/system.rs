@@ -0,0 +1,234 @@
+//! Discretised diffusion system implementation.
+
+use crate::{
+    diffuse::Settings,
+    display_field, display_field_ln,
+    grid::Grid,
+    mcrt::{voxel_materials, Environment, Interface, Material},
+    tree::Cell,
+    Error, Set, Stencil, X, Y, Z,
+};
+use ndarray::Array3;
+use std::fmt::{Display, Formatter, Result};
+
+/// Finite-volume discretisation of the steady-state diffusion equation on a regular grid.
+/// Each diffusive voxel obeys `diag * phi - sum(cond * phi_nb) = source`.
+/// Faces between diffusive and non-diffusive voxels, and grid faces, use extrapolated boundary conditions.
+pub struct System {
+    /// Diffusive voxel flags.
+    diffusive: Array3<bool>,
+    /// Refractive index of each voxel.
+    ref_index: Array3<f64>,
+    /// Absorption coefficient of each voxel [1/m].
+    abs_coeff: Array3<f64>,
+    /// Reduced attenuation coefficient of each voxel [1/m].
+    trans_coeff: Array3<f64>,
+    /// Conductances, per unit volume, between voxels, and of each voxel including absorption and boundary losses [1/m].
+    stencil: Stencil,
+}
+
+impl System {
+    /// Construct a new instance.
+    /// The material of each voxel is that containing its centre.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        sett: &Settings,
+        grid: &Grid,
+        mats: &Set<Material>,
+        inters: &Set<Interface>,
+        tree: &Cell,
+    ) -> Self {
+        let res = *grid.res();
+        let w = sett.wavelength();
+
+        // Determine the optical environment of each voxel.
+        let groups = voxel_materials(tree, inters, grid, sett.init_mat(), sett.bump_dist());
+        let envs: Array3<Environment> = Array3::from_shape_fn(res, |(xi, yi, zi)| {
+            mats.map()[groups[[xi, yi, zi]]].voxel_env(w, [xi, yi, zi])
+        });
+
+        let diffusive = envs.map(|env| reduced_scat(env) >= sett.min_reduced_scat());
+        let ref_index = envs.map(Environment::ref_index);
+        let abs_coeff = envs.map(Environment::abs_coeff);
+        let trans_coeff = envs.map(|env| loss_coeff(env) + reduced_scat(env));
+        let diff_coeff = trans_coeff.map(|mu| 1.0 / (3.0 * mu));
+        let ext_ref_index = mats.map()[sett.init_mat()].env(w).ref_index();
+
+        let mut stencil = Stencil::new(envs.map(loss_coeff));
+        let widths = grid.voxel_size();
+        for ((xi, yi, zi), is_diffusive) in diffusive.indexed_iter() {
+            let index = [xi, yi, zi];
+            if !*is_diffusive {
+                continue;
+            }
+            let d = diff_coeff[index];
+
+            for (axis, dx) in widths.iter().enumerate() {
+                let dx = *dx;
+
+                // Voxels lose light through faces adjoining non-diffusive voxels, or the grid boundary.
+                let boundary = |n_out: f64| {
+                    let a = extrapolation_coeff(ref_index[index] / n_out);
+                    1.0 / ((dx * dx * 0.5 / d) + (2.0 * a * dx))
+                };
+
+                if index[axis] == 0 {
+                    stencil.add_sink(index, boundary(ext_ref_index));
+                } else {
+                    let mut prev = index;
+                    prev[axis] -= 1;
+                    if !diffusive[prev] {
+                        stencil.add_sink(index, boundary(ref_index[prev]));
+                    }
+                }
+
+                if index[axis] + 1 == res[axis] {
+                    stencil.add_sink(index, boundary(ext_ref_index));
+                } else {
+                    let mut next = index;
+                    next[axis] += 1;
+                    if diffusive[next] {
+                        // Interior faces use the harmonic mean diffusion coefficient of the neighbouring voxels.
+                        stencil.connect(index, axis, d, diff_coeff[next], dx);
+                    } else {
+                        stencil.add_sink(index, boundary(ref_index[next]));
+                    }
+                }
+            }
+        }
+
+        Self {
+            diffusive,
+            ref_index,
+            abs_coeff,
+            trans_coeff,
+            stencil,
+        }
+    }
+
+    /// Reference the refractive index of each voxel.
+    #[inline]
+    #[must_use]
+    pub const fn ref_index(&self) -> &Array3<f64> {
+        &self.ref_index
+    }
+
+    /// Reference the absorption coefficient [1/m] of each voxel.
+    #[inline]
+    #[must_use]
+    pub const fn abs_coeff(&self) -> &Array3<f64> {
+        &self.abs_coeff
+    }
+
+    /// Check if a voxel is diffusive.
+    #[inline]
+    #[must_use]
+    pub fn is_diffusive(&self, index: [usize; 3]) -> bool {
+        self.diffusive[index]
+    }
+
+    /// Get the resolution of the system.
+    #[inline]
+    #[must_use]
+    pub fn res(&self) -> [usize; 3] {
+        let shape = self.diffusive.shape();
+        [shape[X], shape[Y], shape[Z]]
+    }
+
+    /// Solve for the fluence rate [W/m^2] due to an isotropic source power density [W/m^3],
+    /// using successive over-relaxation.
+    /// The fluence rate of non-diffusive voxels is zero.
+    /// # Errors
+    /// if the source does not match the system resolution,
+    /// or the iteration does not converge to within the tolerance.
+    #[inline]
+    pub fn solve(
+        &self,
+        sett: &Settings,
+        sources: &Array3<f64>,
+    ) -> std::result::Result<Array3<f64>, Error> {
+        let res = self.res();
+        if sources.shape() != res {
+            return Err(Error::Text(format!(
+                "Source does not match the grid resolution {:?}.",
+                res
+            )));
+        }
+
+        let omega = sett.relaxation();
+        let diags = self.stencil.diags();
+        let mut phi = Array3::zeros(res);
+        for _ in 0..sett.max_iters() {
+            let max_change = self.stencil.sweep(&mut phi, |index, value, neighbours| {
+                if self.diffusive[index] {
+                    let target = (sources[index] + neighbours) / diags[index];
+                    Some(omega.mul_add(target - value, value))
+                } else {
+                    None
+                }
+            });
+            let max_phi = phi.iter().fold(0.0, |max: f64, p| max.max(p.abs()));
+
+            if max_change <= sett.tolerance() * max_phi {
+                return Ok(phi);
+            }
+        }
+
+        Err(Error::Text(format!(
+            "Diffusion solution did not converge within {} iterations.",
+            sett.max_iters()
+        )))
+    }
+
+    /// Get the reduced transport mean free path [m] of a voxel.
+    #[inline]
+    #[must_use]
+    pub fn mean_free_path(&self, index: [usize; 3]) -> f64 {
+        1.0 / self.trans_coeff[index]
+    }
+}
+
+/// Calculate the reduced scattering coefficient [1/m] of an environment.
+#[inline]
+#[must_use]
+pub fn reduced_scat(env: &Environment) -> f64 {
+    env.scat_coeff() * (1.0 - env.asym())
+}
+
+/// Calculate the coefficient [1/m] at which light is lost from the optical range of interest,
+/// through absorption or shifting.
+#[inline]
+#[must_use]
+fn loss_coeff(env: &Environment) -> f64 {
+    env.abs_coeff() + env.shift_coeff()
+}
+
+/// Calculate the extrapolated boundary coefficient, A = (1 + R) / (1 - R),
+/// from the effective internal reflectance R at the given relative refractive index.
+#[inline]
+#[must_use]
+pub fn extrapolation_coeff(n_rel: f64) -> f64 {
+    let r_eff = (-1.440 / (n_rel * n_rel)) + (0.710 / n_rel) + 0.0636f64.mul_add(n_rel, 0.668);
+    let r_eff = r_eff.max(0.0);
+
+    (1.0 + r_eff) / (1.0 - r_eff)
+}
+
+impl Display for System {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let res = self.res();
+        display_field_ln!(
+            fmt,
+            "resolution",
+            format!("[{}, {}, {}]", res[X], res[Y], res[Z])
+        )?;
+        display_field!(
+            fmt,
+            "diffusive voxels",
+            self.diffusive.iter().filter(|d| **d).count()
+        )
+    }
+}
@@ -0,0 +1,91 @@
+//! Thermal boundary condition implementation.
+
+use crate::{display_field, display_field_ln, X, Y, Z};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Thermal boundary condition enumeration.
+#[load]
+#[derive(Clone)]
+pub enum Boundary {
+    /// Fixed temperature [K].
+    Dirichlet(f64),
+    /// Fixed inward heat flux [W/m^2].
+    Neumann(f64),
+    /// Convective exchange: heat transfer coefficient [W/m^2/K], ambient temperature [K].
+    Convective(f64, f64),
+}
+
+impl Boundary {
+    /// Determine the linearised inward heat flux, per unit face area, of a boundary voxel.
+    /// The flux into the voxel is given by `c - g * T`, and returned as `(g, c)`.
+    /// The face lies half a voxel width, `half_width` [m], from the voxel centre.
+    #[inline]
+    #[must_use]
+    pub fn coeffs(&self, conductivity: f64, half_width: f64) -> (f64, f64) {
+        debug_assert!(conductivity > 0.0);
+        debug_assert!(half_width > 0.0);
+
+        match *self {
+            Self::Dirichlet(temp) => {
+                let g = conductivity / half_width;
+                (g, g * temp)
+            }
+            Self::Neumann(flux) => (0.0, flux),
+            Self::Convective(h, temp) => {
+                let g = 1.0 / ((half_width / conductivity) + (1.0 / h));
+                (g, g * temp)
+            }
+        }
+    }
+}
+
+impl Display for Boundary {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Dirichlet(temp) => write!(fmt, "Dirichlet ({} K)", temp),
+            Self::Neumann(flux) => write!(fmt, "Neumann ({} W/m^2)", flux),
+            Self::Convective(h, temp) => write!(fmt, "Convective ({} W/m^2/K, {} K)", h, temp),
+        }
+    }
+}
+
+/// Grid face boundary conditions structure.
+#[load]
+pub struct Boundaries {
+    /// Conditions on the minimum face of each axis.
+    mins: [Boundary; 3],
+    /// Conditions on the maximum face of each axis.
+    maxs: [Boundary; 3],
+}
+
+impl Boundaries {
+    /// Reference the condition on the minimum face of the given axis.
+    #[inline]
+    #[must_use]
+    pub fn min(&self, axis: usize) -> &Boundary {
+        &self.mins[axis]
+    }
+
+    /// Reference the condition on the maximum face of the given axis.
+    #[inline]
+    #[must_use]
+    pub fn max(&self, axis: usize) -> &Boundary {
+        &self.maxs[axis]
+    }
+}
+
+impl Display for Boundaries {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "x min", &self.mins[X])?;
+        display_field_ln!(fmt, "x max", &self.maxs[X])?;
+        display_field_ln!(fmt, "y min", &self.mins[Y])?;
+        display_field_ln!(fmt, "y max", &self.maxs[Y])?;
+        display_field_ln!(fmt, "z min", &self.mins[Z])?;
+        display_field!(fmt, "z max", &self.maxs[Z])
+    }
+}
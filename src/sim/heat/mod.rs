@@ -0,0 +1,11 @@
+//! Pennes bioheat module.
+
+pub mod boundary;
+pub mod output;
+pub mod scheme;
+pub mod settings;
+pub mod solver;
+pub mod system;
+pub mod tissue;
+
+pub use self::{boundary::*, output::*, scheme::*, settings::*, solver::*, system::*, tissue::*};
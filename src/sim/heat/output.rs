@@ -0,0 +1,98 @@
+//! Output data structure.
+
+use crate::{display_field, display_field_ln, Error, Save};
+use ndarray::Array3;
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::Write,
+    path::Path,
+};
+
+/// Temperature snapshot output structure.
+pub struct Output {
+    /// Snapshot times [s].
+    times: Vec<f64>,
+    /// Snapshot temperatures [K].
+    temps: Vec<Array3<f64>>,
+}
+
+impl Output {
+    /// Construct a new empty instance.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            times: Vec::new(),
+            temps: Vec::new(),
+        }
+    }
+
+    /// Reference the snapshot times [s].
+    #[inline]
+    #[must_use]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Reference the snapshot temperatures [K].
+    #[inline]
+    #[must_use]
+    pub fn temps(&self) -> &[Array3<f64>] {
+        &self.temps
+    }
+
+    /// Record a temperature snapshot.
+    #[inline]
+    pub fn push(&mut self, time: f64, temps: Array3<f64>) {
+        debug_assert!(self.times.last().map_or(true, |last| time > *last));
+
+        self.times.push(time);
+        self.temps.push(temps);
+    }
+
+    /// Determine the maximum temperature [K] reached across all snapshots.
+    #[inline]
+    #[must_use]
+    pub fn max_temp(&self) -> f64 {
+        self.temps
+            .iter()
+            .flat_map(|temps| temps.iter())
+            .fold(std::f64::NEG_INFINITY, |max, t| max.max(*t))
+    }
+}
+
+impl Save for Output {
+    #[inline]
+    fn save(&self, out_dir: &Path) -> Result<(), Error> {
+        for (n, temps) in self.temps.iter().enumerate() {
+            let path = out_dir.join(&format!("temperature_{:04}.nc", n));
+            println!("saving: {}", path.display());
+            temps.save(&path)?;
+        }
+
+        let path = out_dir.join("times.csv");
+        println!("saving: {}", path.display());
+        let mut file = File::create(path)?;
+        for (n, time) in self.times.iter().enumerate() {
+            writeln!(file, "{:>8}, {:<32}", n, time)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Output {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(fmt, "snapshots", self.times.len())?;
+        display_field_ln!(
+            fmt,
+            "final time",
+            self.times.last().copied().unwrap_or(0.0),
+            "s"
+        )?;
+        display_field!(fmt, "max temperature", self.max_temp(), "K")
+    }
+}
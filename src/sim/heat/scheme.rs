@@ -0,0 +1,36 @@
+//! Time-stepping scheme implementation.
+
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Time-stepping scheme enumeration.
+#[load]
+pub enum Scheme {
+    /// Forward Euler stepping, subject to a stability limit on the time step.
+    Explicit,
+    /// Unconditionally stable backward Euler stepping, solved by Gauss-Seidel iteration.
+    Implicit {
+        /// Maximum temperature change [K] of the final iteration.
+        tolerance: f64,
+        /// Maximum number of iterations per step.
+        max_iters: u64,
+    },
+}
+
+impl Display for Scheme {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Explicit => write!(fmt, "Explicit"),
+            Self::Implicit {
+                tolerance,
+                max_iters,
+            } => write!(
+                fmt,
+                "Implicit (tolerance: {} K, max iterations: {})",
+                tolerance, max_iters
+            ),
+        }
+    }
+}
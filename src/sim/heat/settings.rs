@@ -0,0 +1,81 @@
+//! Settings implementation.
+
+use crate::{
+    access, clone, display_field, display_field_ln,
+    heat::{Boundaries, Scheme},
+    Group,
+};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Bioheat settings structure.
+#[load]
+pub struct Settings {
+    /// Time step [s].
+    time_step: f64,
+    /// Number of time steps.
+    num_steps: u64,
+    /// Number of time steps between temperature snapshots.
+    snapshot: u64,
+    /// Initial tissue temperature [K].
+    init_temp: f64,
+    /// Arterial blood temperature [K].
+    blood_temp: f64,
+    /// Blood density [kg/m^3].
+    blood_density: f64,
+    /// Blood specific heat capacity [J/kg/K].
+    blood_heat_capacity: f64,
+    /// Bump distance [m].
+    bump_dist: f64,
+    /// Initial material.
+    init_mat: Group,
+    /// Time-stepping scheme.
+    scheme: Scheme,
+    /// Grid face boundary conditions.
+    boundaries: Boundaries,
+}
+
+impl Settings {
+    clone!(time_step, f64);
+    clone!(num_steps, u64);
+    clone!(snapshot, u64);
+    clone!(init_temp, f64);
+    clone!(blood_temp, f64);
+    clone!(blood_density, f64);
+    clone!(blood_heat_capacity, f64);
+    clone!(bump_dist, f64);
+    access!(init_mat, Group);
+    access!(scheme, Scheme);
+    access!(boundaries, Boundaries);
+
+    /// Calculate the total simulated duration [s].
+    #[inline]
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.time_step * self.num_steps as f64
+    }
+}
+
+impl Display for Settings {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "time step", self.time_step, "s")?;
+        display_field_ln!(fmt, "number of steps", self.num_steps)?;
+        display_field_ln!(fmt, "duration", self.duration(), "s")?;
+        display_field_ln!(fmt, "snapshot interval", self.snapshot, "steps")?;
+        display_field_ln!(fmt, "initial temperature", self.init_temp, "K")?;
+        display_field_ln!(fmt, "blood temperature", self.blood_temp, "K")?;
+        display_field_ln!(fmt, "blood density", self.blood_density, "kg/m^3")?;
+        display_field_ln!(
+            fmt,
+            "blood heat capacity",
+            self.blood_heat_capacity,
+            "J/kg/K"
+        )?;
+        display_field_ln!(fmt, "bump distance", self.bump_dist, "m")?;
+        display_field_ln!(fmt, "initial material", &self.init_mat)?;
+        display_field_ln!(fmt, "scheme", &self.scheme)?;
+        display_field!(fmt, "boundaries", &self.boundaries)
+    }
+}
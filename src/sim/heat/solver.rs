@@ -0,0 +1,58 @@
+//! Time-stepping solver functions.
+
+use crate::{
+    heat::{Output, Scheme, Settings, System},
+    Bar, Error,
+};
+use ndarray::Array3;
+
+/// Integrate the bioheat system from the initial temperature,
+/// recording a temperature snapshot at the start, at each snapshot interval, and at the end.
+/// # Errors
+/// if the time step or snapshot interval is not positive,
+/// an explicit time step exceeds the stability limit,
+/// or an implicit step fails to converge.
+#[inline]
+pub fn solve(sys: &System, sett: &Settings) -> Result<Output, Error> {
+    let dt = sett.time_step();
+    if dt <= 0.0 {
+        return Err(Error::Text("Time step must be positive.".to_string()));
+    }
+    if sett.snapshot() == 0 {
+        return Err(Error::Text(
+            "Snapshot interval must be at least one step.".to_string(),
+        ));
+    }
+    if let Scheme::Explicit = sett.scheme() {
+        let max_step = sys.max_stable_step();
+        if dt > max_step {
+            return Err(Error::Text(format!(
+                "Explicit time step {} s exceeds the stability limit {} s.",
+                dt, max_step
+            )));
+        }
+    }
+
+    let mut temps = Array3::from_elem(sys.res(), sett.init_temp());
+    let mut data = Output::new();
+    data.push(0.0, temps.clone());
+
+    let mut pb = Bar::new("Stepping", sett.num_steps());
+    for step in 1..=sett.num_steps() {
+        match sett.scheme() {
+            Scheme::Explicit => sys.explicit_step(&mut temps, dt),
+            Scheme::Implicit {
+                tolerance,
+                max_iters,
+            } => sys.implicit_step(&mut temps, dt, *tolerance, *max_iters)?,
+        }
+
+        if step % sett.snapshot() == 0 || step == sett.num_steps() {
+            data.push(dt * step as f64, temps.clone());
+        }
+        pb.inc(1);
+    }
+    pb.finish_with_message("Heating complete");
+
+    Ok(data)
+}
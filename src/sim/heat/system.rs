@@ -0,0 +1,193 @@
+//! Discretised bioheat system implementation.
+
+use crate::{
+    display_field, display_field_ln,
+    grid::Grid,
+    heat::{Settings, Tissue},
+    mcrt::{voxel_materials, Interface},
+    tree::Cell,
+    Error, Set, Stencil, X, Y, Z,
+};
+use ndarray::Array3;
+use std::fmt::{Display, Formatter, Result};
+
+/// Finite-volume discretisation of the Pennes bioheat equation on a regular grid.
+/// Each voxel obeys `cap * dT/dt = sum(cond * T_nb) - diag * T + source`.
+pub struct System {
+    /// Volumetric heat capacity of each voxel [J/m^3/K].
+    caps: Array3<f64>,
+    /// Conductances, per unit volume, between voxels, and of each voxel including boundary and perfusion sinks [W/m^3/K].
+    stencil: Stencil,
+    /// Constant heat generation within each voxel [W/m^3].
+    sources: Array3<f64>,
+}
+
+impl System {
+    /// Construct a new instance.
+    /// The tissue of each voxel is that containing its centre,
+    /// and the absorbed power density [W/m^3] provides the external heat source.
+    /// # Errors
+    /// if the absorbed power density does not match the grid resolution,
+    /// or a voxel material has no tissue properties.
+    #[inline]
+    pub fn new(
+        sett: &Settings,
+        grid: &Grid,
+        tissues: &Set<Tissue>,
+        inters: &Set<Interface>,
+        tree: &Cell,
+        absorption_dens: &Array3<f64>,
+    ) -> std::result::Result<Self, Error> {
+        let res = *grid.res();
+        if absorption_dens.shape() != res {
+            return Err(Error::Text(format!(
+                "Heat source does not match the grid resolution {:?}.",
+                res
+            )));
+        }
+
+        // Determine the tissue of each voxel.
+        let groups = voxel_materials(tree, inters, grid, sett.init_mat(), sett.bump_dist());
+        let mut voxel_tissues = Vec::with_capacity(grid.total_cells());
+        for group in groups.iter() {
+            voxel_tissues.push(tissues.map().get(*group).ok_or_else(|| {
+                Error::Text(format!("Material {} has no tissue properties.", group))
+            })?);
+        }
+        let voxel_tissues = Array3::from_shape_vec(res, voxel_tissues)?;
+
+        let perf_coeff = sett.blood_density() * sett.blood_heat_capacity();
+        let caps = voxel_tissues.map(|tissue| tissue.vol_heat_capacity());
+        let mut stencil = Stencil::new(voxel_tissues.map(|tissue| tissue.perfusion() * perf_coeff));
+        let mut sources = absorption_dens
+            + &voxel_tissues.map(|tissue| {
+                tissue
+                    .perfusion()
+                    .mul_add(perf_coeff * sett.blood_temp(), tissue.metabolic())
+            });
+
+        let widths = grid.voxel_size();
+        for ((xi, yi, zi), tissue) in voxel_tissues.indexed_iter() {
+            let index = [xi, yi, zi];
+            let k = tissue.conductivity();
+
+            for (axis, dx) in widths.iter().enumerate() {
+                let dx = *dx;
+
+                // Interior faces use the harmonic mean conductivity of the neighbouring voxels.
+                if index[axis] + 1 < res[axis] {
+                    let mut next = index;
+                    next[axis] += 1;
+                    stencil.connect(index, axis, k, voxel_tissues[next].conductivity(), dx);
+                }
+
+                // Grid faces exchange heat according to their boundary condition.
+                if index[axis] == 0 {
+                    let (g, c) = sett.boundaries().min(axis).coeffs(k, dx * 0.5);
+                    stencil.add_sink(index, g / dx);
+                    sources[index] += c / dx;
+                }
+                if index[axis] + 1 == res[axis] {
+                    let (g, c) = sett.boundaries().max(axis).coeffs(k, dx * 0.5);
+                    stencil.add_sink(index, g / dx);
+                    sources[index] += c / dx;
+                }
+            }
+        }
+
+        Ok(Self {
+            caps,
+            stencil,
+            sources,
+        })
+    }
+
+    /// Get the resolution of the system.
+    #[inline]
+    #[must_use]
+    pub fn res(&self) -> [usize; 3] {
+        let shape = self.caps.shape();
+        [shape[X], shape[Y], shape[Z]]
+    }
+
+    /// Determine the largest time step [s] for which explicit stepping is stable.
+    #[inline]
+    #[must_use]
+    pub fn max_stable_step(&self) -> f64 {
+        self.caps
+            .iter()
+            .zip(self.stencil.diags().iter())
+            .filter(|(_cap, diag)| **diag > 0.0)
+            .map(|(cap, diag)| cap / diag)
+            .fold(std::f64::INFINITY, f64::min)
+    }
+
+    /// Advance the temperatures [K] by a forward Euler step [s].
+    #[inline]
+    pub fn explicit_step(&self, temps: &mut Array3<f64>, dt: f64) {
+        debug_assert!(dt > 0.0);
+        debug_assert!(dt <= self.max_stable_step());
+
+        let prev = temps.clone();
+        let diags = self.stencil.diags();
+        for ((xi, yi, zi), temp) in temps.indexed_iter_mut() {
+            let index = [xi, yi, zi];
+            let rate = diags[index].mul_add(-prev[index], self.stencil.neighbours(&prev, index))
+                + self.sources[index];
+            *temp += rate * dt / self.caps[index];
+        }
+    }
+
+    /// Advance the temperatures [K] by a backward Euler step [s].
+    /// The implicit system is solved by Gauss-Seidel iteration,
+    /// which converges as the system is diagonally dominant.
+    /// # Errors
+    /// if the iteration does not converge to within the tolerance [K].
+    #[inline]
+    pub fn implicit_step(
+        &self,
+        temps: &mut Array3<f64>,
+        dt: f64,
+        tolerance: f64,
+        max_iters: u64,
+    ) -> std::result::Result<(), Error> {
+        debug_assert!(dt > 0.0);
+        debug_assert!(tolerance > 0.0);
+
+        let prev = temps.clone();
+        let diags = self.stencil.diags();
+        for _ in 0..max_iters {
+            let max_change = self.stencil.sweep(temps, |index, _temp, neighbours| {
+                let inertia = self.caps[index] / dt;
+                Some(
+                    (inertia.mul_add(prev[index], self.sources[index]) + neighbours)
+                        / (inertia + diags[index]),
+                )
+            });
+
+            if max_change <= tolerance {
+                return Ok(());
+            }
+        }
+
+        Err(Error::Text(format!(
+            "Implicit step did not converge within {} iterations.",
+            max_iters
+        )))
+    }
+}
+
+impl Display for System {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let res = self.res();
+        display_field_ln!(
+            fmt,
+            "resolution",
+            format!("[{}, {}, {}]", res[X], res[Y], res[Z])
+        )?;
+        display_field_ln!(fmt, "max stable step", self.max_stable_step(), "s")?;
+        display_field!(fmt, "total source", self.sources.sum(), "W/m^3")
+    }
+}
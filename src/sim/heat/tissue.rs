@@ -0,0 +1,88 @@
+//! Tissue implementation.
+
+use crate::{
+    clone, display_field, display_field_ln,
+    mcrt::{check_materials, Interface},
+    Error, Set,
+};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Thermal tissue properties structure.
+#[load]
+pub struct Tissue {
+    /// Thermal conductivity [W/m/K].
+    conductivity: f64,
+    /// Density [kg/m^3].
+    density: f64,
+    /// Specific heat capacity [J/kg/K].
+    heat_capacity: f64,
+    /// Volumetric blood perfusion rate [1/s].
+    perfusion: f64,
+    /// Optional metabolic heat generation [W/m^3], defaulting to zero.
+    metabolic: Option<f64>,
+}
+
+impl Tissue {
+    clone!(conductivity, f64);
+    clone!(density, f64);
+    clone!(heat_capacity, f64);
+    clone!(perfusion, f64);
+
+    /// Get the metabolic heat generation [W/m^3].
+    #[inline]
+    #[must_use]
+    pub fn metabolic(&self) -> f64 {
+        self.metabolic.unwrap_or(0.0)
+    }
+
+    /// Calculate the volumetric heat capacity [J/m^3/K].
+    #[inline]
+    #[must_use]
+    pub fn vol_heat_capacity(&self) -> f64 {
+        self.density * self.heat_capacity
+    }
+}
+
+/// Check that every tissue property value is physical, that every surface group has an interface,
+/// and that the initial material and every interface refers to a known tissue.
+/// # Errors
+/// if a property is non-positive, or negative for perfusion,
+/// a surface group has no interface,
+/// or a material is not mapped to a tissue.
+#[inline]
+pub fn check_tissues<T>(
+    surfs: &Set<T>,
+    inters: &Set<Interface>,
+    tissues: &Set<Tissue>,
+    init_mat: &str,
+) -> std::result::Result<(), Error> {
+    for (group, tissue) in tissues.map() {
+        if tissue.conductivity <= 0.0 || tissue.density <= 0.0 || tissue.heat_capacity <= 0.0 {
+            return Err(Error::Text(format!(
+                "Tissue {} conductivity, density and heat capacity must be positive.",
+                group
+            )));
+        }
+        if tissue.perfusion < 0.0 {
+            return Err(Error::Text(format!(
+                "Tissue {} perfusion must be non-negative.",
+                group
+            )));
+        }
+    }
+
+    check_materials(surfs, inters, tissues, init_mat)
+}
+
+impl Display for Tissue {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "conductivity", self.conductivity, "W/m/K")?;
+        display_field_ln!(fmt, "density", self.density, "kg/m^3")?;
+        display_field_ln!(fmt, "heat capacity", self.heat_capacity, "J/kg/K")?;
+        display_field_ln!(fmt, "perfusion", self.perfusion, "1/s")?;
+        display_field!(fmt, "metabolic heat", self.metabolic(), "W/m^3")
+    }
+}
@@ -0,0 +1,146 @@
+//! Checkpoint implementation.
+
+use crate::{clone, mcrt::Output, resume_stream, Error, Prng};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_reader, to_writer};
+use std::{
+    fs::{rename, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Progress record of a single simulation thread.
+/// Restoring a checkpoint and continuing reproduces the uninterrupted run exactly.
+#[derive(Deserialize, Serialize)]
+pub struct Checkpoint {
+    /// Master random number seed.
+    seed: u64,
+    /// Total number of threads in the run.
+    num_threads: usize,
+    /// Thread index.
+    thread_id: usize,
+    /// Grid resolution of the accumulated data.
+    res: [usize; 3],
+    /// Number of photons in each uncertainty batch, if estimation is enabled.
+    batch_size: Option<u64>,
+    /// Number of photons completed.
+    count: u64,
+    /// Word position within the thread's random number stream.
+    word_pos: u128,
+    /// Accumulated output data.
+    data: Output,
+}
+
+impl Checkpoint {
+    clone!(count, u64);
+
+    /// Construct a new instance for a thread which has not yet started.
+    #[inline]
+    #[must_use]
+    pub const fn new(
+        seed: u64,
+        num_threads: usize,
+        thread_id: usize,
+        res: [usize; 3],
+        batch_size: Option<u64>,
+        data: Output,
+    ) -> Self {
+        Self {
+            seed,
+            num_threads,
+            thread_id,
+            res,
+            batch_size,
+            count: 0,
+            word_pos: 0,
+            data,
+        }
+    }
+
+    /// Determine the checkpoint file path for a given thread within a directory.
+    #[inline]
+    #[must_use]
+    pub fn path(dir: &Path, thread_id: usize) -> PathBuf {
+        dir.join(format!("thread_{}.json", thread_id))
+    }
+
+    /// Load the checkpoint of a given thread from a directory, checking that it belongs to the same run.
+    /// # Errors
+    /// if the checkpoint file can not be read,
+    /// if it was written by a run with a different seed or thread count,
+    /// or if it was written by a run with a different grid resolution or uncertainty batch size.
+    #[inline]
+    pub fn load(
+        dir: &Path,
+        seed: u64,
+        num_threads: usize,
+        thread_id: usize,
+        res: [usize; 3],
+        batch_size: Option<u64>,
+    ) -> Result<Self, Error> {
+        let path = Self::path(dir, thread_id);
+        println!("loading: {}", path.display());
+        let check: Self = from_reader(BufReader::new(File::open(&path)?))?;
+
+        if check.seed != seed || check.num_threads != num_threads || check.thread_id != thread_id {
+            return Err(Error::Text(format!(
+                "Checkpoint {} was written by a run with a different seed or thread count.",
+                path.display()
+            )));
+        }
+        if check.res != res || check.batch_size != batch_size {
+            return Err(Error::Text(format!(
+                "Checkpoint {} was written by a run with a different grid resolution or uncertainty batch size.",
+                path.display()
+            )));
+        }
+
+        Ok(check)
+    }
+
+    /// Construct the generator positioned where the checkpoint left off.
+    #[inline]
+    #[must_use]
+    pub fn rng(&self) -> Prng {
+        resume_stream(self.seed, self.thread_id as u64, self.word_pos)
+    }
+
+    /// Reference the accumulated output data mutably.
+    #[inline]
+    #[must_use]
+    pub fn data_mut(&mut self) -> &mut Output {
+        &mut self.data
+    }
+
+    /// Record the completion of a block of photons.
+    #[inline]
+    pub fn advance(&mut self, block: u64) {
+        self.count += block;
+    }
+
+    /// Destruct self into the accumulated output data.
+    #[inline]
+    #[must_use]
+    pub fn into_data(self) -> Output {
+        self.data
+    }
+
+    /// Write the checkpoint, with the current generator state, into a directory.
+    /// The file is written in full before replacing any previous checkpoint,
+    /// so an interruption while saving leaves the last checkpoint intact.
+    /// # Errors
+    /// if the checkpoint can not be serialised or written.
+    #[inline]
+    pub fn save(&mut self, dir: &Path, rng: &Prng) -> Result<(), Error> {
+        self.word_pos = rng.get_word_pos();
+
+        let path = Self::path(dir, self.thread_id);
+        let tmp_path = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        to_writer(&mut writer, self)?;
+        writer.flush()?;
+        rename(&tmp_path, &path)?;
+
+        Ok(())
+    }
+}
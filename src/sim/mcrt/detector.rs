@@ -0,0 +1,184 @@
+//! Detector implementation.
+
+use crate::{clone, display_field, display_field_ln, Binner, Dir3, Error, Mesh, Range, Set};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Detector acceptance filter enumeration.
+#[load]
+#[derive(Clone)]
+pub enum Acceptance {
+    /// Numerical aperture.
+    NumericalAperture(f64),
+    /// Acceptance half-angle [deg].
+    Angle(f64),
+}
+
+impl Acceptance {
+    /// Determine the maximum accepted angle of incidence [rad] within a medium of the given refractive index.
+    #[inline]
+    #[must_use]
+    pub fn max_angle(&self, ref_index: f64) -> f64 {
+        debug_assert!(ref_index >= 1.0);
+
+        match self {
+            Self::NumericalAperture(na) => (na / ref_index).min(1.0).asin(),
+            Self::Angle(theta) => theta.to_radians(),
+        }
+    }
+}
+
+impl Display for Acceptance {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::NumericalAperture(na) => display_field!(fmt, "numerical aperture", na),
+            Self::Angle(theta) => display_field!(fmt, "acceptance angle", theta, "deg"),
+        }
+    }
+}
+
+/// Detector estimator enumeration.
+#[load]
+#[derive(Clone, Copy)]
+pub enum Estimator {
+    /// Tally photons as they cross the detector surface.
+    Analog,
+    /// Tally the expected contribution toward the detector surface at each scattering event.
+    /// Photons crossing the surface are only tallied directly if their flight could not be estimated,
+    /// such as following emission, re-emission, reflection or refraction.
+    NextEvent,
+}
+
+impl Display for Estimator {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let kind = match self {
+            Self::Analog => "Analog",
+            Self::NextEvent => "Next-event",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
+
+/// Surface detector structure.
+#[load]
+pub struct Detector {
+    /// Wavelength range [m].
+    range: Range,
+    /// Number of wavelength bins.
+    bins: u64,
+    /// Optional acceptance filter.
+    acceptance: Option<Acceptance>,
+    /// When true photons are absorbed by the detector, otherwise they pass through.
+    absorb: bool,
+    /// Optional estimator, defaulting to analog.
+    estimator: Option<Estimator>,
+}
+
+impl Detector {
+    clone!(acceptance, Option<Acceptance>);
+    clone!(absorb, bool);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        range: Range,
+        bins: u64,
+        acceptance: Option<Acceptance>,
+        absorb: bool,
+        estimator: Option<Estimator>,
+    ) -> Self {
+        debug_assert!(bins > 0);
+
+        Self {
+            range,
+            bins,
+            acceptance,
+            absorb,
+            estimator,
+        }
+    }
+
+    /// Determine the estimator.
+    #[inline]
+    #[must_use]
+    pub fn estimator(&self) -> Estimator {
+        self.estimator.unwrap_or(Estimator::Analog)
+    }
+
+    /// Determine if the detector is tallied by next-event estimation.
+    #[inline]
+    #[must_use]
+    pub fn next_event(&self) -> bool {
+        if let Estimator::NextEvent = self.estimator() {
+            return true;
+        }
+
+        false
+    }
+
+    /// Create the wavelength binner.
+    #[inline]
+    #[must_use]
+    pub fn binner(&self) -> Binner {
+        Binner::new(self.range.clone(), self.bins)
+    }
+
+    /// Determine if a photon travelling in the given direction is accepted.
+    /// The normal must face against the direction of travel.
+    #[inline]
+    #[must_use]
+    pub fn accepts(&self, dir: &Dir3, norm: &Dir3, ref_index: f64) -> bool {
+        if let Some(acceptance) = &self.acceptance {
+            let theta = (-dir.dot(norm)).min(1.0).acos();
+            return theta <= acceptance.max_angle(ref_index);
+        }
+
+        true
+    }
+}
+
+/// Check that every detector is bound to an existing surface group.
+/// Next-event detectors must also be bound to a surface with a non-zero area to sample.
+/// # Errors
+/// if a detector refers to a surface group which does not exist,
+/// or a next-event detector surface has no area.
+#[inline]
+pub fn check_detectors(surfs: &Set<Mesh>, dets: &Set<Detector>) -> std::result::Result<(), Error> {
+    for (group, det) in dets.map() {
+        let surf = surfs.map().get(group).ok_or_else(|| {
+            Error::Text(format!(
+                "Detector {} is not bound to a surface group.",
+                group
+            ))
+        })?;
+        if det.next_event() && surf.area() <= 0.0 {
+            return Err(Error::Text(format!(
+                "Next-event detector {} surface has no area.",
+                group
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for Detector {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "wavelength range", &self.range, "m")?;
+        display_field_ln!(fmt, "bins", self.bins)?;
+        if let Some(acceptance) = &self.acceptance {
+            display_field_ln!(fmt, "acceptance", acceptance)?;
+        } else {
+            display_field_ln!(fmt, "acceptance", "all")?;
+        }
+        display_field_ln!(fmt, "absorb", self.absorb)?;
+        display_field!(fmt, "estimator", self.estimator())
+    }
+}
@@ -0,0 +1,201 @@
+//! Photon event diagnostics.
+
+use crate::{as_json, display_field, display_field_ln, Aabb, Error, Pos3, Save, X, Y, Z};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter, Result},
+    ops::AddAssign,
+    path::Path,
+};
+
+/// Axis labels of the grid faces.
+const AXES: [&str; 3] = ["x", "y", "z"];
+
+/// Tally of a single kind of photon event.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct Count {
+    /// Number of occurrences.
+    pub num: u64,
+    /// Total statistical weight.
+    pub weight: f64,
+    /// Total energy [J].
+    pub energy: f64,
+}
+
+impl Count {
+    /// Record an occurrence of a given weight and energy [J].
+    #[inline]
+    pub fn record(&mut self, weight: f64, energy: f64) {
+        self.num += 1;
+        self.weight += weight;
+        self.energy += energy;
+    }
+}
+
+impl AddAssign<&Self> for Count {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.num += rhs.num;
+        self.weight += rhs.weight;
+        self.energy += rhs.energy;
+    }
+}
+
+impl Display for Count {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        write!(
+            fmt,
+            "{} events, {:.6e} weight, {:.6e} [J]",
+            self.num, self.weight, self.energy
+        )
+    }
+}
+
+/// Photon termination and event diagnostics.
+/// Energies [J] are those carried by each photon's weighted power over a unit emission time.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct Diagnostics {
+    /// Emitted photons.
+    pub emitted: Count,
+    /// Weight lost to absorption at interaction events.
+    pub absorbed: Count,
+    /// Photons leaving the grid, through the minimum and maximum faces of each axis.
+    pub escaped: [[Count; 2]; 3],
+    /// Photons absorbed by a detector.
+    pub detected: Count,
+    /// Photons terminated on reaching the loop limit.
+    pub loop_limit: Count,
+    /// Photons terminated by roulette.
+    pub roulette_killed: Count,
+    /// Weight gained by photons surviving roulette.
+    pub roulette_survived: Count,
+    /// Scattering events.
+    pub scatterings: Count,
+    /// Shift events, and the energy lost in each.
+    pub shifts: Count,
+    /// Partial reflections from an interface.
+    pub reflections: Count,
+    /// Total internal reflections.
+    pub total_internal_reflections: Count,
+    /// Refractions through an interface.
+    pub refractions: Count,
+    /// Detector tallies, including those of non-absorbing detectors.
+    pub detections: Count,
+    /// Photons re-entering the grid through a periodic face.
+    pub wraps: Count,
+}
+
+impl Diagnostics {
+    /// Record a photon leaving the grid from a given exit position.
+    /// The face is that which the position lies furthest beyond.
+    #[inline]
+    pub fn record_escape(&mut self, boundary: &Aabb, pos: &Pos3, weight: f64, energy: f64) {
+        let mut face = (X, 0);
+        let mut excess = std::f64::NEG_INFINITY;
+        for axis in &[X, Y, Z] {
+            let below = boundary.mins()[*axis] - pos[*axis];
+            let above = pos[*axis] - boundary.maxs()[*axis];
+            if below > excess {
+                excess = below;
+                face = (*axis, 0);
+            }
+            if above > excess {
+                excess = above;
+                face = (*axis, 1);
+            }
+        }
+
+        let (axis, side) = face;
+        self.escaped[axis][side].record(weight, energy);
+    }
+
+    /// Calculate the total energy [J] which has left the grid.
+    #[inline]
+    #[must_use]
+    pub fn escaped_energy(&self) -> f64 {
+        self.escaped
+            .iter()
+            .flat_map(|faces| faces.iter())
+            .map(|count| count.energy)
+            .sum()
+    }
+
+    /// Calculate the fraction of the emitted energy which is not accounted for by the sinks:
+    /// absorption, escape, detection, and termination and shift losses, less the gain from roulette.
+    /// This should be close to zero.
+    #[inline]
+    #[must_use]
+    pub fn imbalance(&self) -> f64 {
+        let sinks = self.absorbed.energy
+            + self.escaped_energy()
+            + self.detected.energy
+            + self.loop_limit.energy
+            + self.roulette_killed.energy
+            + self.shifts.energy
+            - self.roulette_survived.energy;
+
+        (self.emitted.energy - sinks) / self.emitted.energy
+    }
+}
+
+impl AddAssign<&Self> for Diagnostics {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.emitted += &rhs.emitted;
+        self.absorbed += &rhs.absorbed;
+        for (faces, rhs_faces) in self.escaped.iter_mut().zip(rhs.escaped.iter()) {
+            for (count, rhs_count) in faces.iter_mut().zip(rhs_faces.iter()) {
+                *count += rhs_count;
+            }
+        }
+        self.detected += &rhs.detected;
+        self.loop_limit += &rhs.loop_limit;
+        self.roulette_killed += &rhs.roulette_killed;
+        self.roulette_survived += &rhs.roulette_survived;
+        self.scatterings += &rhs.scatterings;
+        self.shifts += &rhs.shifts;
+        self.reflections += &rhs.reflections;
+        self.total_internal_reflections += &rhs.total_internal_reflections;
+        self.refractions += &rhs.refractions;
+        self.detections += &rhs.detections;
+        self.wraps += &rhs.wraps;
+    }
+}
+
+impl Display for Diagnostics {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "emitted", &self.emitted)?;
+        display_field_ln!(fmt, "absorbed", &self.absorbed)?;
+        for (axis, faces) in AXES.iter().zip(self.escaped.iter()) {
+            display_field_ln!(fmt, &format!("escaped -{}", axis), &faces[0])?;
+            display_field_ln!(fmt, &format!("escaped +{}", axis), &faces[1])?;
+        }
+        display_field_ln!(fmt, "detected", &self.detected)?;
+        display_field_ln!(fmt, "loop limit", &self.loop_limit)?;
+        display_field_ln!(fmt, "roulette killed", &self.roulette_killed)?;
+        display_field_ln!(fmt, "roulette survived", &self.roulette_survived)?;
+        display_field_ln!(fmt, "scatterings", &self.scatterings)?;
+        display_field_ln!(fmt, "shifts", &self.shifts)?;
+        display_field_ln!(fmt, "reflections", &self.reflections)?;
+        display_field_ln!(
+            fmt,
+            "total internal reflections",
+            &self.total_internal_reflections
+        )?;
+        display_field_ln!(fmt, "refractions", &self.refractions)?;
+        display_field_ln!(fmt, "detections", &self.detections)?;
+        display_field_ln!(fmt, "wraps", &self.wraps)?;
+        display_field!(fmt, "energy imbalance", 100.0 * self.imbalance(), "%")
+    }
+}
+
+impl Save for Diagnostics {
+    #[inline]
+    fn save(&self, path: &Path) -> std::result::Result<(), Error> {
+        as_json(self, path)
+    }
+}
@@ -2,46 +2,55 @@
 
 use crate::{
     grid::Grid,
-    mcrt::{Light, Material, Settings},
+    mcrt::{Detector, Interface, Light, Material, Settings},
     tree::Cell,
-    Set,
+    Mesh, Set,
 };
 
 /// Input data collection.
 pub struct Input<'a> {
     /// Simulation settings.
     pub sett: &'a Settings,
-    /// Emission source.
-    pub light: &'a Light,
+    /// Emission sources.
+    pub lights: &'a Set<Light>,
     /// Materials.
     pub mats: &'a Set<Material>,
+    /// Surface material interfaces.
+    pub inters: &'a Set<Interface>,
+    /// Surface detectors.
+    pub dets: &'a Set<Detector>,
+    /// Surfaces.
+    pub surfs: &'a Set<Mesh>,
     /// Surface tree.
     pub tree: &'a Cell<'a>,
     /// Regular grid.
     pub grid: &'a Grid,
-    /// Simulation kind flag. // TODO remove.
-    pub kind: i32,
 }
 
 impl<'a> Input<'a> {
     /// Construct a new instance.
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     #[must_use]
     pub const fn new(
         sett: &'a Settings,
-        light: &'a Light,
+        lights: &'a Set<Light>,
         mats: &'a Set<Material>,
+        inters: &'a Set<Interface>,
+        dets: &'a Set<Detector>,
+        surfs: &'a Set<Mesh>,
         tree: &'a Cell,
         grid: &'a Grid,
-        kind: i32,
     ) -> Self {
         Self {
             sett,
-            light,
+            lights,
             mats,
+            inters,
+            dets,
+            surfs,
             tree,
             grid,
-            kind,
         }
     }
 }
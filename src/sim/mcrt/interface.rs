@@ -0,0 +1,208 @@
+//! Material interface implementation.
+
+use crate::{
+    access, display_field, display_field_ln,
+    grid::Grid,
+    mcrt::{Detector, Material},
+    tree::Cell,
+    Error, Group, Microfacet, Ray, Set, Side, Vec3,
+};
+use attr::load;
+use ndarray::Array3;
+use std::fmt::{Display, Formatter, Result};
+
+/// Material interface structure.
+/// Describes the materials either side of a surface group.
+#[load]
+pub struct Interface {
+    /// Material on the inside of the surface.
+    inside: Group,
+    /// Material on the outside of the surface.
+    outside: Group,
+    /// Optional microfacet roughness of the surface.
+    /// Smooth surfaces cross at their macroscopic normal.
+    roughness: Option<Microfacet>,
+}
+
+impl Interface {
+    access!(inside, Group);
+    access!(outside, Group);
+    access!(roughness, Option<Microfacet>);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub const fn new(inside: Group, outside: Group, roughness: Option<Microfacet>) -> Self {
+        Self {
+            inside,
+            outside,
+            roughness,
+        }
+    }
+
+    /// Determine the material on the near side of the surface for a hit on the given side.
+    #[inline]
+    #[must_use]
+    pub fn curr(&self, side: &Side) -> &Group {
+        if side.is_inside() {
+            &self.inside
+        } else {
+            &self.outside
+        }
+    }
+
+    /// Determine the material on the far side of the surface for a hit on the given side.
+    #[inline]
+    #[must_use]
+    pub fn next(&self, side: &Side) -> &Group {
+        if side.is_inside() {
+            &self.outside
+        } else {
+            &self.inside
+        }
+    }
+}
+
+/// Determine the material at the start of a ray from the first interface surface it meets.
+/// Surfaces without an interface, such as detectors, are passed through.
+/// Returns None if the ray meets no interface.
+#[inline]
+#[must_use]
+pub fn resolve_material<'a>(
+    tree: &Cell,
+    inters: &'a Set<Interface>,
+    ray: &Ray,
+    bump_dist: f64,
+) -> Option<&'a Group> {
+    let mut ray = ray.clone();
+    while let Some(hit) = tree.observe(ray.clone(), bump_dist, std::f64::INFINITY) {
+        if let Some(inter) = inters.map().get(hit.group()) {
+            return Some(inter.curr(hit.side()));
+        }
+
+        ray.travel(hit.dist() + bump_dist);
+    }
+
+    None
+}
+
+/// Determine the material containing the centre of each voxel of a grid.
+/// Voxels which meet no interface lie within the initial material.
+#[inline]
+#[must_use]
+pub fn voxel_materials<'a>(
+    tree: &Cell,
+    inters: &'a Set<Interface>,
+    grid: &Grid,
+    init_mat: &'a Group,
+    bump_dist: f64,
+) -> Array3<&'a Group> {
+    Array3::from_shape_fn(*grid.res(), |(xi, yi, zi)| {
+        let ray = Ray::new(grid.voxel([xi, yi, zi]).centre(), Vec3::z_axis());
+        resolve_material(tree, inters, &ray, bump_dist).unwrap_or(init_mat)
+    })
+}
+
+/// Check that every non-detector surface group has an interface, that the initial material is known,
+/// and that every interface refers to known materials and has a valid roughness.
+/// # Errors
+/// if a surface group is not mapped to an interface or detector,
+/// if the initial material does not exist,
+/// if an interface refers to a material which does not exist,
+/// or if an interface roughness is negative or not finite.
+#[inline]
+pub fn check_interfaces<T>(
+    surfs: &Set<T>,
+    inters: &Set<Interface>,
+    dets: &Set<Detector>,
+    mats: &Set<Material>,
+    init_mat: &str,
+) -> std::result::Result<(), Error> {
+    if !mats.map().contains_key(init_mat) {
+        return Err(Error::Text(format!(
+            "Initial material {} is unknown.",
+            init_mat
+        )));
+    }
+
+    for group in surfs.map().keys() {
+        if !inters.map().contains_key(group) && !dets.map().contains_key(group) {
+            return Err(Error::Text(format!(
+                "Surface group {} has no material interface.",
+                group
+            )));
+        }
+    }
+
+    for (group, inter) in inters.map() {
+        for mat in &[inter.inside(), inter.outside()] {
+            if !mats.map().contains_key(*mat) {
+                return Err(Error::Text(format!(
+                    "Interface {} refers to unknown material {}.",
+                    group, mat
+                )));
+            }
+        }
+
+        if let Some(roughness) = inter.roughness() {
+            if !roughness.alpha().is_finite() || roughness.alpha() < 0.0 {
+                return Err(Error::Text(format!(
+                    "Interface {} roughness must be finite and non-negative.",
+                    group
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that every surface group has an interface,
+/// and that the initial material and every interface refers to a known material.
+/// Unlike `check_interfaces`, no surfaces are treated as detectors.
+/// # Errors
+/// if a surface group has no interface,
+/// or a material is not known.
+#[inline]
+pub fn check_materials<T, M>(
+    surfs: &Set<T>,
+    inters: &Set<Interface>,
+    mats: &Set<M>,
+    init_mat: &str,
+) -> std::result::Result<(), Error> {
+    for group in surfs.map().keys() {
+        if !inters.map().contains_key(group) {
+            return Err(Error::Text(format!(
+                "Surface group {} has no material interface.",
+                group
+            )));
+        }
+    }
+
+    let mut groups: Vec<&str> = vec![init_mat];
+    for inter in inters.map().values() {
+        groups.push(inter.inside());
+        groups.push(inter.outside());
+    }
+    for group in groups {
+        if !mats.map().contains_key(group) {
+            return Err(Error::Text(format!("Unknown material {}.", group)));
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for Interface {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "inside", &self.inside)?;
+        display_field_ln!(fmt, "outside", &self.outside)?;
+        if let Some(roughness) = &self.roughness {
+            display_field!(fmt, "roughness", roughness)
+        } else {
+            display_field!(fmt, "roughness", "smooth")
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! Photon lifetime function module.
 
-use crate::mcrt::{Input, Output};
-use rand::rngs::ThreadRng;
+use crate::{
+    mcrt::{Input, Output},
+    Prng,
+};
 
 /// Photon lifetime function type.
-pub type Life = fn(&Input, &mut Output, &mut ThreadRng);
+/// The final argument flags whether the photon's path should be recorded.
+pub type Life = fn(&Input, &mut Output, &mut Prng, bool);
 
 pub mod test;
 
@@ -2,18 +2,27 @@
 
 use crate::{
     distribution,
-    mcrt::{Environment, Event, Input, Material, Output, Photon},
-    Crossing, Hit, Set, Trace,
+    mcrt::{
+        resolve_material, select_light, total_power, Environment, Event, Input, Interface,
+        Material, Output, Photon, Polarisation, Reemission, Step, Trajectory,
+    },
+    Crossing, Dir3, Hit, Prng, Range, Ray, Set, Trace,
 };
 use physical_constants::SPEED_OF_LIGHT_IN_VACUUM;
-use rand::{rngs::ThreadRng, Rng};
-use std::f64::consts::PI;
+use rand::Rng;
+use std::mem::replace;
+
+/// Refractive index difference below which an interface is treated as index-matched by next-event estimation.
+const INDEX_TOLERANCE: f64 = 1.0e-9;
+
+/// Number of microfacet normals sampled at a rough surface before falling back to the macroscopic normal.
+const MAX_FACET_SAMPLES: usize = 100;
 
 /// Test photon lifetime function.
+/// If record is set, the path of the photon is appended to the output.
 #[allow(clippy::option_expect_used)]
-#[allow(clippy::single_match)]
 #[inline]
-pub fn test(input: &Input, data: &mut Output, rng: &mut ThreadRng) {
+pub fn test(input: &Input, data: &mut Output, rng: &mut Prng, record: bool) {
     // Useful constants.
     let bump_dist = input.sett.bump_dist();
     let loop_limit = input.sett.loop_limit();
@@ -21,26 +30,75 @@ pub fn test(input: &Input, data: &mut Output, rng: &mut ThreadRng) {
     let roulette_survive_prob = 1.0 / input.sett.roulette_barrels() as f64;
 
     // Photon variable initialisation.
-    let (mut phot, mat) = emit_phot(input, rng);
-    let mut env = mat.env(phot.wavelength());
-    // data.paths.push(Vec::new());
+    let (mut phot, mut mat) = emit_phot(input, rng);
+    let mut traj = if record {
+        Some(Trajectory::new(*phot.ray().pos(), Step::Emission))
+    } else {
+        None
+    };
 
     // Check photon can be placed within the grid domain.
-    if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
+    let mut env = if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
         data.emitted_photons[index] += phot.weight();
+        data.diagnostics
+            .emitted
+            .record(phot.weight(), phot.energy());
+        mat.voxel_env(phot.wavelength(), index)
     } else {
         panic!("Photon was not emitted within the grid.");
-    }
+    };
 
-    // Loop photon life until it leaves the grid.
+    // Set when the flight since the last scattering event is tallied by next-event detectors.
+    let mut estimated = false;
+
+    // Loop photon life until it leaves the grid through a non-periodic face.
     let mut loops = 0;
-    while let Some((index, voxel)) = input.grid.gen_index_voxel(phot.ray().pos()) {
-        // Record photon position.
-        // data.paths.last_mut().unwrap().push(*phot.ray().pos());
+    loop {
+        let (index, voxel) = if let Some(index_voxel) = input.grid.gen_index_voxel(phot.ray().pos())
+        {
+            index_voxel
+        } else {
+            let exit_pos = *phot.ray().pos();
+            if input
+                .grid
+                .wrap(phot.ray_mut().pos_mut(), input.sett.periodic())
+            {
+                // Re-enter through the opposite periodic face with the same direction.
+                // The recorded path is split so that no segment crosses the grid.
+                if let Some(traj) = &mut traj {
+                    traj.push(exit_pos, Step::Exit);
+                    let wrapped = Trajectory::new(*phot.ray().pos(), Step::Wrap);
+                    data.paths.push(replace(traj, wrapped));
+                }
+                data.diagnostics.wraps.record(phot.weight(), phot.energy());
+                estimated = false;
+
+                // The material at the entry point need not match that at the exit point.
+                mat = start_material(input, phot.ray());
+                if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
+                    env = mat.voxel_env(phot.wavelength(), index);
+                }
+                continue;
+            }
+            data.diagnostics.record_escape(
+                input.grid.boundary(),
+                &exit_pos,
+                phot.weight(),
+                phot.energy(),
+            );
+            break;
+        };
+
+        // Voxel-resolved materials vary at each voxel crossing.
+        if mat.volume().is_some() {
+            env = mat.voxel_env(phot.wavelength(), index);
+        }
 
         // Check if loop limit has been reached.
         if loops >= loop_limit {
-            println!("Warning! Terminating photon: loop limit reached.");
+            data.diagnostics
+                .loop_limit
+                .record(phot.weight(), phot.energy());
             break;
         }
         loops += 1;
@@ -49,8 +107,15 @@ pub fn test(input: &Input, data: &mut Output, rng: &mut ThreadRng) {
         if phot.weight() <= roulette_weight {
             let r = rng.gen::<f64>();
             if r > roulette_survive_prob {
+                data.diagnostics
+                    .roulette_killed
+                    .record(phot.weight(), phot.energy());
                 break;
             }
+            let gain = input.sett.roulette_barrels() as f64 - 1.0;
+            data.diagnostics
+                .roulette_survived
+                .record(phot.weight() * gain, phot.energy() * gain);
             *phot.weight_mut() *= input.sett.roulette_barrels() as f64;
         }
 
@@ -71,41 +136,113 @@ pub fn test(input: &Input, data: &mut Output, rng: &mut ThreadRng) {
             Event::Voxel(dist) => travel(data, index, &env, &mut phot, dist + bump_dist),
             // Interaction event.
             Event::Scattering(dist) => {
-                scatter(data, rng, index, &env, &mut phot, dist);
+                estimated = scatter(input, data, rng, index, mat, &mut env, &mut phot, dist);
+                if let Some(traj) = &mut traj {
+                    traj.push(*phot.ray().pos(), Step::Scatter);
+                }
             }
             // Interface collision.
             Event::Surface(hit) => {
                 // Move to the collision point.
                 travel(data, index, &env, &mut phot, hit.dist());
 
-                // Special collision events.
-                match hit.group() {
-                    "spectrometer" => {
-                        travel(data, index, &env, &mut phot, bump_dist);
-                        data.spec.collect_weight(phot.wavelength(), phot.weight());
-                        continue;
+                // Detector collision.
+                if let Some(det) = input.dets.map().get(hit.group()) {
+                    if !(estimated && det.next_event())
+                        && det.accepts(phot.ray().dir(), hit.side().norm(), env.ref_index())
+                    {
+                        detect(input, data, hit.group(), &phot);
                     }
-                    _ => {}
+                    if det.absorb() {
+                        data.diagnostics
+                            .detected
+                            .record(phot.weight(), phot.energy());
+                        break;
+                    }
+                    travel(data, index, &env, &mut phot, bump_dist);
+                    continue;
                 }
 
                 // Get the near, and far side refractive indices.
                 let curr_ref = env.ref_index();
-                let next_env = select_property(&hit, input.mats).env(phot.wavelength());
+                let next_mat = select_property(&hit, input.inters, input.mats);
+                let next_env = next_mat.voxel_env(phot.wavelength(), index);
                 let next_ref = next_env.ref_index();
+                if (next_ref - curr_ref).abs() > INDEX_TOLERANCE {
+                    estimated = false;
+                }
+
+                // Rough surfaces cross at a microfacet normal, resampled whilst the outgoing direction is masked.
+                let inc = *phot.ray().dir();
+                let norm = hit.side().norm();
+                let roughness = input.inters.map()[hit.group()].roughness();
+                let mut samples = 0;
+                let (crossing, reflect) = loop {
+                    samples += 1;
+                    let roughness = roughness.as_ref().filter(|_| samples < MAX_FACET_SAMPLES);
+                    let facet = roughness
+                        .map_or(*norm, |roughness| roughness.sample_normal(rng, &inc, norm));
+                    let crossing = Crossing::new(&inc, &facet, curr_ref, next_ref);
+
+                    // Polarised photons reflect according to their s- and p-polarised components.
+                    let ref_prob = phot.pol_mut().as_mut().map_or(crossing.ref_prob(), |pol| {
+                        pol.align(&inc, &facet);
+                        crossing.ref_mueller().intensity(pol.stokes())
+                    });
+                    let reflect = rng.gen::<f64>() <= ref_prob;
 
-                // Calculate the crossing normal vectors.
-                let crossing =
-                    Crossing::new(phot.ray().dir(), hit.side().norm(), curr_ref, next_ref);
+                    if let Some(roughness) = roughness {
+                        let (out, side) = if reflect {
+                            (*crossing.ref_dir(), 1.0)
+                        } else {
+                            (crossing.trans_dir().expect("Invalid refraction."), -1.0)
+                        };
+                        if (out.dot(norm) * side) <= 0.0
+                            || rng.gen::<f64>() > roughness.masking(&out, norm)
+                        {
+                            continue;
+                        }
+                    }
+
+                    break (crossing, reflect);
+                };
 
                 // Determine if a reflection or transmission occurs.
-                let r = rng.gen::<f64>();
-                if r <= crossing.ref_prob() {
+                let (mueller, step) = if reflect {
                     // Reflect.
+                    if crossing.trans_dir().is_some() {
+                        data.diagnostics
+                            .reflections
+                            .record(phot.weight(), phot.energy());
+                    } else {
+                        data.diagnostics
+                            .total_internal_reflections
+                            .record(phot.weight(), phot.energy());
+                    }
                     *phot.ray_mut().dir_mut() = *crossing.ref_dir();
+                    (crossing.ref_mueller(), Step::Reflection)
                 } else {
                     // Refract.
+                    data.diagnostics
+                        .refractions
+                        .record(phot.weight(), phot.energy());
                     *phot.ray_mut().dir_mut() = crossing.trans_dir().expect("Invalid refraction.");
+                    mat = next_mat;
                     env = next_env;
+                    (
+                        crossing
+                            .trans_mueller()
+                            .as_ref()
+                            .expect("Invalid refraction."),
+                        Step::Refraction,
+                    )
+                };
+                if let Some(traj) = &mut traj {
+                    traj.push(*phot.ray().pos(), step);
+                }
+                let dir = *phot.ray().dir();
+                if let Some(pol) = phot.pol_mut() {
+                    pol.cross(mueller, &inc, &dir);
                 }
 
                 // Move slightly away from the surface.
@@ -114,109 +251,397 @@ pub fn test(input: &Input, data: &mut Output, rng: &mut ThreadRng) {
         }
     }
 
-    // data.paths.last_mut().unwrap().push(*phot.ray().pos());
+    if let Some(mut traj) = traj {
+        traj.push(*phot.ray().pos(), Step::Exit);
+        data.paths.push(traj);
+    }
 }
 
 /// Generate a new photon.
 #[inline]
 #[must_use]
-fn emit_phot<'a>(input: &'a Input, rng: &mut ThreadRng) -> (Photon, &'a Material) {
-    // Generate photons from the light source until they're in the optical range of interest.
-    let mut phot;
-    loop {
-        phot = input.light.emit(input.sett.num_phot(), rng);
-        if input.sett.range().contains(phot.wavelength()) {
-            break;
-        }
+fn emit_phot<'a>(input: &'a Input, rng: &mut Prng) -> (Photon, &'a Material) {
+    // Each photon carries an equal share of the total power, drawn from sources in proportion to their power.
+    let phot_power = total_power(input.lights) / input.sett.num_phot() as f64;
+
+    // Generate a photon within the optical range of interest.
+    let (source, light) = select_light(input.lights, rng);
+    let mut phot = light.emit(phot_power, input.sett.range(), input.grid.boundary(), rng);
+    if input.sett.tag_sources() {
+        *phot.source_mut() = Some(source);
     }
 
-    // Select the required material.
-    let mat = if input.kind == 4 {
-        &input.mats.map()["flesh"]
-    } else {
-        &input.mats.map()[input.sett.init_mat()]
-    };
+    // Set the initial polarisation state.
+    if let Some(polariser) = input.sett.polariser() {
+        *phot.pol_mut() = Some(polariser.polarise(phot.ray().dir()));
+    }
+
+    let mat = start_material(input, phot.ray());
 
     (phot, mat)
 }
 
+/// Determine the material at the start of a ray from the surrounding geometry.
+/// Rays which meet no interface start within the initial material.
+#[inline]
+#[must_use]
+fn start_material<'a>(input: &'a Input, ray: &Ray) -> &'a Material {
+    let group = resolve_material(input.tree, input.inters, ray, input.sett.bump_dist())
+        .unwrap_or_else(|| input.sett.init_mat());
+
+    &input.mats.map()[group]
+}
+
 /// Move the photon forward and record the flight.
 #[inline]
 fn travel(data: &mut Output, index: [usize; 3], env: &Environment, phot: &mut Photon, dist: f64) {
     debug_assert!(dist > 0.0);
 
+    let flight_time = dist * (env.ref_index() / SPEED_OF_LIGHT_IN_VACUUM);
+
     let weight_power_dist = phot.weight() * phot.power() * dist;
-    data.energy[index] += weight_power_dist * (env.ref_index() / SPEED_OF_LIGHT_IN_VACUUM);
-    data.absorptions[index] += weight_power_dist * env.abs_coeff();
+    let energy = phot.weight() * phot.power() * flight_time;
+    let absorption = weight_power_dist * env.abs_coeff();
+    if phot.shifted() {
+        data.shifted_energy[index] += energy;
+    } else {
+        data.energy[index] += energy;
+    }
+    data.absorptions[index] += absorption;
     data.shifts[index] += weight_power_dist * env.shift_coeff();
+    if let Some(source) = phot.source() {
+        if let Some(source_energy) = data.source_energy.values_mut().nth(source) {
+            source_energy[index] += energy;
+        }
+    }
+
+    // Time-resolved tallies are recorded at the midpoint of the flight.
+    if let Some(time_bin) = data.time_bin(flight_time.mul_add(0.5, phot.time())) {
+        let [xi, yi, zi] = index;
+        if let Some(time_energy) = &mut data.time_energy {
+            time_energy[[xi, yi, zi, time_bin]] += energy;
+        }
+        if let Some(time_absorptions) = &mut data.time_absorptions {
+            time_absorptions[[xi, yi, zi, time_bin]] += absorption;
+        }
+    }
 
     data.dist_travelled[index] += dist;
 
     phot.ray_mut().travel(dist);
+    *phot.time_mut() += flight_time;
+}
+
+/// Record a photon arriving at a detector.
+#[inline]
+fn detect(input: &Input, data: &mut Output, group: &str, phot: &Photon) {
+    data.diagnostics
+        .detections
+        .record(phot.weight(), phot.energy());
+    if let Some(hist) = data.dets.get_mut(group) {
+        hist.try_collect_weight(phot.wavelength(), phot.weight());
+    }
+    if let Some(hist) = data.time_dets.get_mut(group) {
+        hist.try_collect_weight(phot.time(), phot.weight());
+    }
+    if let (Some(polariser), Some(pol), Some(stokes)) = (
+        input.sett.polariser(),
+        phot.pol(),
+        data.det_stokes.get_mut(group),
+    ) {
+        *stokes += &(polariser.measure(phot.ray().dir(), pol) * phot.weight());
+    }
 }
 
 /// Perform a photon scattering event.
+/// Returns true if the photon was scattered, rather than shifted.
+#[allow(clippy::too_many_arguments)]
 #[inline]
+#[must_use]
 fn scatter(
+    input: &Input,
     data: &mut Output,
-    rng: &mut ThreadRng,
+    rng: &mut Prng,
     index: [usize; 3],
-    env: &Environment,
+    mat: &Material,
+    env: &mut Environment,
     phot: &mut Photon,
     dist: f64,
-) {
+) -> bool {
     debug_assert!(dist > 0.0);
 
     // Move to the interaction point.
     travel(data, index, env, phot, dist);
 
     // Part of the weight is absorbed.
+    let absorbed = 1.0 - env.albedo();
+    data.diagnostics
+        .absorbed
+        .record(phot.weight() * absorbed, phot.energy() * absorbed);
     *phot.weight_mut() *= env.albedo();
 
     // The remaining weight may be shifted in a Raman/fluorescence event.
     let r = rng.gen::<f64>();
     if r <= env.shift_prob() {
         // Shift occurs.
-        // Fluorescence event removes photons from optical range of interest.
-        *phot.weight_mut() = 0.0;
-        return;
+        let (weight, energy) = (phot.weight(), phot.energy());
+        if let Some(reemission) = mat.reemission() {
+            reemit(rng, reemission, input.sett.range(), phot);
+            if phot.weight() > 0.0 {
+                *env = mat.voxel_env(phot.wavelength(), index);
+            }
+        } else {
+            // Without a re-emission spectrum the photon is removed from the optical range of interest.
+            *phot.weight_mut() = 0.0;
+        }
+        data.diagnostics
+            .shifts
+            .record(weight, energy - phot.energy());
+        return false;
     }
+    data.diagnostics
+        .scatterings
+        .record(phot.weight(), phot.energy());
+
+    // The expected contribution of the scattered weight is tallied toward next-event detectors.
+    next_event(input, data, rng, mat, env, phot);
 
     // The remaining weight is scattered.
-    let phi = distribution::henyey_greenstein(rng, env.asym());
-    let theta = rng.gen_range(0.0, PI * 2.0);
-    phot.ray_mut().rotate(phi, theta);
+    let pitch = mat.phase().gen(rng, phot.wavelength(), env.asym());
+    let mueller = phot.pol().as_ref().map(|_| {
+        mat.phase()
+            .mueller(phot.wavelength(), env.asym(), pitch.cos())
+    });
+    phot.scatter(rng, pitch, mueller.as_ref());
+
+    true
 }
 
-/// Determine the next material from the hit event information.
-#[must_use]
+/// Tally the expected contribution of a scattered photon toward each next-event detector.
+/// A point is sampled uniformly over each detector surface,
+/// and the photon weight is scaled by the phase function density toward it,
+/// the solid angle it subtends, and the probability of reaching it without interaction.
+/// Paths which cross an interface between differing refractive indices, or an absorbing detector, contribute nothing,
+/// as the photons following them are tallied directly.
 #[inline]
-pub fn select_property<'a>(hit: &Hit, mats: &'a Set<Material>) -> &'a Material {
-    match hit.group() {
-        "skin" => {
-            if hit.side().is_inside() {
-                &mats.map()["air"]
-            } else {
-                &mats.map()["flesh"]
-            }
+fn next_event(
+    input: &Input,
+    data: &mut Output,
+    rng: &mut Prng,
+    mat: &Material,
+    env: &Environment,
+    phot: &Photon,
+) {
+    for (group, det) in input.dets.map() {
+        if !det.next_event() {
+            continue;
         }
-        "tumour_body" => {
-            if hit.side().is_inside() {
-                &mats.map()["flesh"]
-            } else {
-                &mats.map()["tumour"]
-            }
+
+        // Sample a target point on the detector surface.
+        let surf = &input.surfs.map()[group];
+        let target = surf.cast_flat(rng);
+        if input.grid.gen_index(target.pos()).is_none() {
+            continue;
+        }
+        let disp = target.pos() - phot.ray().pos();
+        let dist = disp.magnitude();
+        if dist <= input.sett.bump_dist() {
+            continue;
         }
-        "tumour_cap" => {
-            if hit.side().is_inside() {
-                &mats.map()["air"]
+        let dir = Dir3::new_normalize(disp);
+
+        // Trace the path to the target.
+        let (norm, optical_depth, flight_time, ref_index) =
+            if let Some(path) = transmit(input, group, mat, phot, &dir, dist) {
+                path
             } else {
-                &mats.map()["tumour"]
+                continue;
+            };
+        if !det.accepts(&dir, &norm, ref_index) {
+            continue;
+        }
+
+        // Polarised photons scatter according to the polarised phase function.
+        let cos_theta = phot.ray().dir().dot(&dir).max(-1.0).min(1.0);
+        let (pol, density) = if let Some(pol) = phot.pol() {
+            let mueller = mat
+                .phase()
+                .mueller(phot.wavelength(), env.asym(), cos_theta);
+            let (pol, density) = pol.scattered(phot.ray().dir(), &dir, &mueller);
+            (Some(pol), density)
+        } else {
+            (
+                None,
+                mat.phase().pdf(phot.wavelength(), env.asym(), cos_theta),
+            )
+        };
+        let solid_angle = surf.area() * dir.dot(target.dir()).abs() / dist.powi(2);
+        let weight = phot.weight() * density * solid_angle * (-optical_depth).exp();
+
+        // Tally a virtual photon arriving at the target.
+        let mut virt = Photon::new(
+            Ray::new(*target.pos(), dir),
+            phot.wavelength(),
+            phot.power(),
+        );
+        *virt.weight_mut() = weight;
+        *virt.time_mut() = phot.time() + flight_time;
+        *virt.pol_mut() = pol;
+        detect(input, data, group, &virt);
+    }
+}
+
+/// Trace a straight path toward a point, a given distance away, on a detector surface.
+/// Returns the detector surface normal, facing against the direction of travel,
+/// the optical depth and flight time [s] accumulated along the path,
+/// and the refractive index at arrival.
+/// Returns None if the path is blocked.
+#[inline]
+#[must_use]
+fn transmit<'a>(
+    input: &'a Input,
+    group: &str,
+    mut mat: &'a Material,
+    phot: &Photon,
+    dir: &Dir3,
+    dist: f64,
+) -> Option<(Dir3, f64, f64, f64)> {
+    let bump_dist = input.sett.bump_dist();
+    let w = phot.wavelength();
+
+    let mut ray = Ray::new(*phot.ray().pos(), *dir);
+    let mut remaining = dist;
+    let mut optical_depth = 0.0;
+    let mut flight_time = 0.0;
+    loop {
+        let hit = input
+            .tree
+            .observe(ray.clone(), bump_dist, remaining + bump_dist)?;
+        if hit.dist() > (remaining + bump_dist) {
+            return None;
+        }
+
+        // Move to the collision point.
+        let (tau, time, ref_index) = attenuate(input, mat, w, &ray, hit.dist());
+        optical_depth += tau;
+        flight_time += time;
+        ray.travel(hit.dist());
+        remaining -= hit.dist();
+
+        if let Some(det) = input.dets.map().get(hit.group()) {
+            // Target reached.
+            if hit.group() == group && remaining <= bump_dist {
+                return Some((*hit.side().norm(), optical_depth, flight_time, ref_index));
+            }
+            if det.absorb() {
+                return None;
             }
+        } else {
+            // Only index-matched interfaces may be crossed without deviation.
+            let next_mat = select_property(&hit, input.inters, input.mats);
+            let index = input.grid.gen_index(ray.pos())?;
+            if (next_mat.voxel_env(w, index).ref_index() - ref_index).abs() > INDEX_TOLERANCE {
+                return None;
+            }
+            mat = next_mat;
+        }
+
+        // Move slightly away from the surface.
+        let (tau, time, _) = attenuate(input, mat, w, &ray, bump_dist);
+        optical_depth += tau;
+        flight_time += time;
+        ray.travel(bump_dist);
+        remaining -= bump_dist;
+        if remaining <= 0.0 {
+            return None;
         }
-        _ => panic!(format!(
+    }
+}
+
+/// Determine the optical depth and flight time [s] of a straight flight through a material,
+/// and the refractive index at its end.
+/// Voxel-resolved materials are integrated voxel by voxel.
+#[inline]
+#[must_use]
+fn attenuate(input: &Input, mat: &Material, w: f64, ray: &Ray, dist: f64) -> (f64, f64, f64) {
+    let bump_dist = input.sett.bump_dist();
+
+    let mut ray = ray.clone();
+    let mut remaining = dist;
+    let mut optical_depth = 0.0;
+    let mut flight_time = 0.0;
+    let mut ref_index = mat.env(w).ref_index();
+    while remaining > 0.0 {
+        let (index, voxel) = if let Some(index_voxel) = input.grid.gen_index_voxel(ray.pos()) {
+            index_voxel
+        } else {
+            break;
+        };
+        let env = mat.voxel_env(w, index);
+
+        let step = if mat.volume().is_some() {
+            voxel
+                .dist(&ray)
+                .map_or(remaining, |d| (d + bump_dist).min(remaining))
+        } else {
+            remaining
+        };
+
+        optical_depth += env.inter_coeff() * step;
+        flight_time += step * (env.ref_index() / SPEED_OF_LIGHT_IN_VACUUM);
+        ref_index = env.ref_index();
+
+        ray.travel(step);
+        remaining -= step;
+    }
+
+    (optical_depth, flight_time, ref_index)
+}
+
+/// Re-emit a photon isotropically at a new wavelength, within the wavelength range [m], following a shift event.
+/// The weight is scaled by the fraction of the re-emission spectrum within the range,
+/// and photons whose spectrum lies entirely outside of it are removed.
+#[inline]
+fn reemit(rng: &mut Prng, reemission: &Reemission, range: &Range, phot: &mut Photon) {
+    // Sample the shifted wavelength, conserving energy per emitted photon.
+    let (wavelength, frac) = if let Some(sample) = reemission.spec().gen_within(rng, range) {
+        sample
+    } else {
+        *phot.weight_mut() = 0.0;
+        return;
+    };
+    *phot.power_mut() *= phot.wavelength() / wavelength;
+    *phot.wavelength_mut() = wavelength;
+    *phot.weight_mut() *= reemission.quantum_yield() * frac;
+    *phot.shifted_mut() = true;
+
+    // Delay the emission by the excited state lifetime.
+    if let Some(lifetime) = reemission.lifetime() {
+        *phot.time_mut() -= lifetime * rng.gen_range(0.0_f64, 1.0).ln();
+    }
+
+    *phot.ray_mut().dir_mut() = distribution::isotropic(rng);
+
+    // Re-emitted light is depolarised.
+    if phot.pol().is_some() {
+        *phot.pol_mut() = Some(Polarisation::new_unpolarised(phot.ray().dir()));
+    }
+}
+
+/// Determine the next material from the hit event information.
+#[must_use]
+#[inline]
+pub fn select_property<'a>(
+    hit: &Hit,
+    inters: &Set<Interface>,
+    mats: &'a Set<Material>,
+) -> &'a Material {
+    let inter = inters.map().get(hit.group()).unwrap_or_else(|| {
+        panic!(
             "Do not know how to handle collision with group {}",
             hit.group()
-        )),
-    }
+        )
+    });
+
+    &mats.map()[inter.next(hit.side())]
 }
@@ -1,12 +1,17 @@
 //! Light structure.
 
 use crate::{
-    display_field, display_field_ln, distribution::isotropic, mcrt::Photon, Emit, Mesh, Pos3,
-    Probability, Ray,
+    display_field, display_field_ln,
+    distribution::{isotropic, normal},
+    mcrt::Photon,
+    Aabb, Dir3, Error, Mesh, Pos3, Probability, Profile, Range, Ray, Set, Vec3,
 };
 use ndarray::Array1;
-use rand::{rngs::ThreadRng, Rng};
-use std::fmt::{Display, Formatter, Result};
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result},
+};
 
 /// Photon emission enumeration.
 pub enum Light {
@@ -14,6 +19,8 @@ pub enum Light {
     Surface {
         /// Surface.
         surf: Mesh,
+        /// Angular emission profile.
+        profile: Profile,
         /// Emission Formula.
         spec: Probability,
         /// Power [J/s].
@@ -28,16 +35,87 @@ pub enum Light {
         /// Emission Formula.
         spec: Probability,
     },
+    /// Voxel field type, emitting isotropically from within weighted voxels.
+    Field {
+        /// Voxel boundaries and their cumulative emission probabilities.
+        voxels: Vec<(Aabb, f64)>,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+    /// Isotropic point type.
+    Point {
+        /// Emission position.
+        pos: Pos3,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+    /// Pencil beam type.
+    Pencil {
+        /// Emission position.
+        pos: Pos3,
+        /// Beam direction.
+        dir: Dir3,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+    /// Collimated top-hat beam type.
+    Beam {
+        /// Beam centre.
+        pos: Pos3,
+        /// Beam direction.
+        dir: Dir3,
+        /// Beam radius [m].
+        radius: f64,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+    /// Gaussian beam type.
+    Gaussian {
+        /// Beam waist centre.
+        pos: Pos3,
+        /// Beam direction.
+        dir: Dir3,
+        /// Beam waist 1/e^2 intensity radius [m].
+        waist: f64,
+        /// Far-field 1/e^2 intensity divergence half-angle [rad].
+        divergence: f64,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+    /// Distant directional type, illuminating the grid through the faces it enters.
+    Directional {
+        /// Illumination direction.
+        dir: Dir3,
+        /// Emission Formula.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
 }
 
 impl Light {
     /// Construct a new surface instance.
     #[inline]
     #[must_use]
-    pub fn new(surf: Mesh, spec: Probability, power: f64) -> Self {
+    pub fn new(surf: Mesh, profile: Profile, spec: Probability, power: f64) -> Self {
         debug_assert!(power > 0.0);
 
-        Self::Surface { surf, spec, power }
+        Self::Surface {
+            surf,
+            profile,
+            spec,
+            power,
+        }
     }
 
     /// Construct a new points instance.
@@ -64,13 +142,111 @@ impl Light {
         }
     }
 
+    /// Construct a new voxel field instance from weighted voxels.
+    /// Voxels with zero weight are discarded.
+    #[inline]
+    #[must_use]
+    pub fn new_field(voxels: Vec<(Aabb, f64)>, spec: Probability, power: f64) -> Self {
+        debug_assert!(power > 0.0);
+
+        let mut voxels: Vec<_> = voxels.into_iter().filter(|(_v, w)| *w > 0.0).collect();
+        debug_assert!(!voxels.is_empty());
+
+        let total_weight: f64 = voxels.iter().map(|(_v, w)| w).sum();
+
+        let mut cum = 0.0;
+        for (_v, w) in &mut voxels {
+            cum += *w / total_weight;
+            *w = cum;
+        }
+
+        Self::Field {
+            voxels,
+            spec,
+            power,
+        }
+    }
+
+    /// Construct a new isotropic point instance.
+    #[inline]
+    #[must_use]
+    pub fn new_point(pos: Pos3, spec: Probability, power: f64) -> Self {
+        debug_assert!(power > 0.0);
+
+        Self::Point { pos, spec, power }
+    }
+
+    /// Construct a new pencil beam instance.
+    #[inline]
+    #[must_use]
+    pub fn new_pencil(pos: Pos3, dir: Dir3, spec: Probability, power: f64) -> Self {
+        debug_assert!(power > 0.0);
+
+        Self::Pencil {
+            pos,
+            dir,
+            spec,
+            power,
+        }
+    }
+
+    /// Construct a new collimated top-hat beam instance.
+    #[inline]
+    #[must_use]
+    pub fn new_beam(pos: Pos3, dir: Dir3, radius: f64, spec: Probability, power: f64) -> Self {
+        debug_assert!(radius > 0.0);
+        debug_assert!(power > 0.0);
+
+        Self::Beam {
+            pos,
+            dir,
+            radius,
+            spec,
+            power,
+        }
+    }
+
+    /// Construct a new Gaussian beam instance.
+    #[inline]
+    #[must_use]
+    pub fn new_gaussian(
+        pos: Pos3,
+        dir: Dir3,
+        waist: f64,
+        divergence: f64,
+        spec: Probability,
+        power: f64,
+    ) -> Self {
+        debug_assert!(waist > 0.0);
+        debug_assert!(divergence >= 0.0);
+        debug_assert!(power > 0.0);
+
+        Self::Gaussian {
+            pos,
+            dir,
+            waist,
+            divergence,
+            spec,
+            power,
+        }
+    }
+
+    /// Construct a new distant directional instance.
+    #[inline]
+    #[must_use]
+    pub fn new_directional(dir: Dir3, spec: Probability, power: f64) -> Self {
+        debug_assert!(power > 0.0);
+
+        Self::Directional { dir, spec, power }
+    }
+
     /// Reference the light's surface mesh.
     #[inline]
     #[must_use]
     pub fn surf(&self) -> &Mesh {
         match self {
             Self::Surface { surf, .. } => surf,
-            Self::Points { .. } => {
+            _ => {
                 panic!("No surface.");
             }
         }
@@ -81,7 +257,14 @@ impl Light {
     #[must_use]
     pub fn spec(&self) -> &Probability {
         match self {
-            Self::Surface { spec, .. } | Self::Points { spec, .. } => spec,
+            Self::Surface { spec, .. }
+            | Self::Points { spec, .. }
+            | Self::Field { spec, .. }
+            | Self::Point { spec, .. }
+            | Self::Pencil { spec, .. }
+            | Self::Beam { spec, .. }
+            | Self::Gaussian { spec, .. }
+            | Self::Directional { spec, .. } => spec,
         }
     }
 
@@ -90,16 +273,24 @@ impl Light {
     #[must_use]
     pub fn power(&self) -> f64 {
         match self {
-            Self::Surface { power, .. } | Self::Points { power, .. } => *power,
+            Self::Surface { power, .. }
+            | Self::Points { power, .. }
+            | Self::Field { power, .. }
+            | Self::Point { power, .. }
+            | Self::Pencil { power, .. }
+            | Self::Beam { power, .. }
+            | Self::Gaussian { power, .. }
+            | Self::Directional { power, .. } => *power,
         }
     }
 
     /// Generate a ray.
+    /// Directional sources emit from the faces of the given grid boundary.
     #[inline]
     #[must_use]
-    pub fn gen_ray(&self, mut rng: &mut ThreadRng) -> Ray {
+    pub fn gen_ray<R: Rng>(&self, boundary: &Aabb, mut rng: &mut R) -> Ray {
         match self {
-            Self::Surface { surf, .. } => surf.cast(rng),
+            Self::Surface { surf, profile, .. } => surf.cast_profile(profile, rng),
             Self::Points { points, .. } => {
                 let r = rng.gen::<f64>();
                 for (p, x) in points {
@@ -110,21 +301,77 @@ impl Light {
                 }
                 panic!("Invalid point weightings.");
             }
+            Self::Field { voxels, .. } => {
+                let r = rng.gen::<f64>();
+                let index = voxels
+                    .binary_search_by(|(_v, cum)| {
+                        cum.partial_cmp(&r).expect("Invalid voxel weightings.")
+                    })
+                    .unwrap_or_else(|index| index)
+                    .min(voxels.len() - 1);
+
+                let voxel = &voxels[index].0;
+                let pos = voxel.mins()
+                    + Vec3::new(rng.gen(), rng.gen(), rng.gen()).component_mul(&voxel.widths());
+                Ray::new(pos, isotropic(rng))
+            }
+            Self::Point { pos, .. } => Ray::new(*pos, isotropic(rng)),
+            Self::Pencil { pos, dir, .. } => Ray::new(*pos, *dir),
+            Self::Beam {
+                pos, dir, radius, ..
+            } => {
+                let (u, v) = perpendicular(dir);
+                let r = radius * rng.gen_range(0.0_f64, 1.0).sqrt();
+                let theta = rng.gen_range(0.0, 2.0 * PI);
+
+                Ray::new(
+                    pos + (u * (r * theta.cos())) + (v * (r * theta.sin())),
+                    *dir,
+                )
+            }
+            Self::Gaussian {
+                pos,
+                dir,
+                waist,
+                divergence,
+                ..
+            } => {
+                // The 1/e^2 radii correspond to two standard deviations of each transverse axis.
+                let (u, v) = perpendicular(dir);
+                let offset = (u * normal(rng) + v * normal(rng)) * (waist * 0.5);
+                let tilt = (u * normal(rng) + v * normal(rng)) * (divergence * 0.5).tan();
+
+                Ray::new(pos + offset, Dir3::new_normalize(dir.as_ref() + tilt))
+            }
+            Self::Directional { dir, .. } => directional(boundary, dir, rng),
         }
     }
 
-    /// Emit a photon.
+    /// Emit a photon, with a wavelength [m] within the given range, carrying the given power [J/s].
+    /// The power is scaled by the fraction of the spectrum lying within the range,
+    /// so that the photon only carries its share of the power emitted within the range.
+    /// # Panics
+    /// if the range excludes the whole spectrum.
+    #[allow(clippy::option_expect_used)]
     #[inline]
     #[must_use]
-    pub fn emit(&self, total_phot: u64, rng: &mut ThreadRng) -> Photon {
-        debug_assert!(total_phot > 0);
+    pub fn emit<R: Rng>(
+        &self,
+        phot_power: f64,
+        range: &Range,
+        boundary: &Aabb,
+        rng: &mut R,
+    ) -> Photon {
+        debug_assert!(phot_power > 0.0);
 
-        let ray = self.gen_ray(rng);
+        let ray = self.gen_ray(boundary, rng);
 
-        let wavelength = self.spec().gen(rng);
-        let power = self.power() / total_phot as f64;
+        let (wavelength, frac) = self
+            .spec()
+            .gen_within(rng, range)
+            .expect("Light spectrum lies outside of the wavelength range.");
 
-        Photon::new(ray, wavelength, power)
+        Photon::new(ray, wavelength, phot_power * frac)
     }
 }
 
@@ -133,14 +380,160 @@ impl Display for Light {
     #[inline]
     fn fmt(&self, fmt: &mut Formatter) -> Result {
         match self {
-            Self::Surface { .. } => {
+            Self::Surface { profile, .. } => {
                 display_field_ln!(fmt, "type", "Emission surface")?;
+                display_field_ln!(fmt, "profile", profile)?;
             }
             Self::Points { .. } => {
                 display_field_ln!(fmt, "type", "Point array")?;
             }
+            Self::Field { voxels, .. } => {
+                display_field_ln!(fmt, "type", "Voxel field")?;
+                display_field_ln!(fmt, "emitting voxels", voxels.len())?;
+            }
+            Self::Point { pos, .. } => {
+                display_field_ln!(fmt, "type", "Isotropic point")?;
+                display_field_ln!(fmt, "position", pos, "m")?;
+            }
+            Self::Pencil { pos, dir, .. } => {
+                display_field_ln!(fmt, "type", "Pencil beam")?;
+                display_field_ln!(fmt, "position", pos, "m")?;
+                display_field_ln!(
+                    fmt,
+                    "direction",
+                    &format!("[{:.2}, {:.2}, {:.2}]", dir.x, dir.y, dir.z)
+                )?;
+            }
+            Self::Beam {
+                pos, dir, radius, ..
+            } => {
+                display_field_ln!(fmt, "type", "Collimated top-hat beam")?;
+                display_field_ln!(fmt, "position", pos, "m")?;
+                display_field_ln!(
+                    fmt,
+                    "direction",
+                    &format!("[{:.2}, {:.2}, {:.2}]", dir.x, dir.y, dir.z)
+                )?;
+                display_field_ln!(fmt, "radius", radius, "m")?;
+            }
+            Self::Gaussian {
+                pos,
+                dir,
+                waist,
+                divergence,
+                ..
+            } => {
+                display_field_ln!(fmt, "type", "Gaussian beam")?;
+                display_field_ln!(fmt, "waist position", pos, "m")?;
+                display_field_ln!(
+                    fmt,
+                    "direction",
+                    &format!("[{:.2}, {:.2}, {:.2}]", dir.x, dir.y, dir.z)
+                )?;
+                display_field_ln!(fmt, "waist radius", waist, "m")?;
+                display_field_ln!(fmt, "divergence", divergence, "rad")?;
+            }
+            Self::Directional { dir, .. } => {
+                display_field_ln!(fmt, "type", "Distant directional")?;
+                display_field_ln!(
+                    fmt,
+                    "direction",
+                    &format!("[{:.2}, {:.2}, {:.2}]", dir.x, dir.y, dir.z)
+                )?;
+            }
         }
         display_field_ln!(fmt, "formula", self.spec())?;
         display_field!(fmt, "power", self.power(), "J/s")
     }
 }
+
+/// Construct a pair of unit vectors perpendicular to the given direction, and to each other.
+#[inline]
+#[must_use]
+fn perpendicular(dir: &Dir3) -> (Vec3, Vec3) {
+    let arbitrary_axis = if (1.0 - dir.z.abs()) >= 1.0e-1 {
+        Vec3::z_axis()
+    } else {
+        Vec3::y_axis()
+    };
+
+    let u = dir.cross(&arbitrary_axis).normalize();
+    let v = dir.cross(&u);
+
+    (u, v)
+}
+
+/// Generate a ray entering the boundary from a distant source in the given direction.
+/// Each face facing the source is selected in proportion to its projected area.
+#[inline]
+#[must_use]
+fn directional<R: Rng>(boundary: &Aabb, dir: &Dir3, rng: &mut R) -> Ray {
+    let widths = boundary.widths();
+    let projected = [
+        widths.y * widths.z * dir.x.abs(),
+        widths.z * widths.x * dir.y.abs(),
+        widths.x * widths.y * dir.z.abs(),
+    ];
+
+    let mut r = rng.gen_range(0.0, projected.iter().sum::<f64>());
+    let mut axis = 0;
+    while axis < 2 && r > projected[axis] {
+        r -= projected[axis];
+        axis += 1;
+    }
+
+    let mut pos =
+        boundary.mins() + Vec3::new(rng.gen(), rng.gen(), rng.gen()).component_mul(&widths);
+    pos[axis] = if dir[axis] > 0.0 {
+        boundary.mins()[axis]
+    } else {
+        boundary.maxs()[axis]
+    };
+
+    // Start a negligible distance within the face, so that the photon is inside the grid.
+    let mut ray = Ray::new(pos, *dir);
+    ray.travel(widths[axis] * 1.0e-9 / dir[axis].abs());
+
+    ray
+}
+
+/// Determine the total power [J/s] of a set of lights.
+#[inline]
+#[must_use]
+pub fn total_power(lights: &Set<Light>) -> f64 {
+    lights.map().values().map(Light::power).sum()
+}
+
+/// Check that every light emits some of its spectrum within a wavelength range.
+/// # Errors
+/// if the range excludes the whole spectrum of a light.
+#[inline]
+pub fn check_spectra(lights: &Set<Light>, range: &Range) -> std::result::Result<(), Error> {
+    for (group, light) in lights.map() {
+        if light.spec().fraction(range) <= 0.0 {
+            return Err(Error::Text(format!(
+                "Light {} spectrum lies entirely outside of the wavelength range {}.",
+                group, range
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Select a light, and its index within the set, with a probability proportional to its power.
+#[inline]
+#[must_use]
+pub fn select_light<'a, R: Rng>(lights: &'a Set<Light>, rng: &mut R) -> (usize, &'a Light) {
+    let mut r = rng.gen_range(0.0, total_power(lights));
+    let mut chosen = None;
+    for (index, light) in lights.map().values().enumerate() {
+        chosen = Some((index, light));
+        if r <= light.power() {
+            break;
+        }
+        r -= light.power();
+    }
+
+    chosen.expect("Empty light set.")
+}
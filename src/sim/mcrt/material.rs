@@ -1,6 +1,10 @@
 //! Material structure.
 
-use crate::{access, display_field, display_field_ln, mcrt::Environment, Formula};
+use crate::{
+    access, display_field, display_field_ln,
+    mcrt::{Environment, Phase, Reemission, Volume},
+    Formula,
+};
 use std::fmt::{Display, Formatter, Result};
 
 /// Wavelength [m] to use when printing example values.
@@ -18,6 +22,12 @@ pub struct Material {
     shift_coeff: Option<Formula>,
     /// Asymmetry factor.
     asym_fact: Formula,
+    /// Scattering phase function.
+    phase: Phase,
+    /// Optional re-emission following shift events.
+    reemission: Option<Reemission>,
+    /// Optional voxel property volume.
+    volume: Option<Volume>,
 }
 
 impl Material {
@@ -26,8 +36,12 @@ impl Material {
     access!(abs_coeff, Option<Formula>);
     access!(shift_coeff, Option<Formula>);
     access!(asym_fact, Formula);
+    access!(phase, Phase);
+    access!(reemission, Option<Reemission>);
+    access!(volume, Option<Volume>);
 
     /// Construct a new instance.
+    #[allow(clippy::too_many_arguments)]
     #[inline]
     #[must_use]
     pub const fn new(
@@ -36,6 +50,9 @@ impl Material {
         abs_coeff: Option<Formula>,
         shift_coeff: Option<Formula>,
         asym_fact: Formula,
+        phase: Phase,
+        reemission: Option<Reemission>,
+        volume: Option<Volume>,
     ) -> Self {
         Self {
             ref_index,
@@ -43,6 +60,9 @@ impl Material {
             abs_coeff,
             shift_coeff,
             asym_fact,
+            phase,
+            reemission,
+            volume,
         }
     }
 
@@ -70,6 +90,28 @@ impl Material {
 
         Environment::new(index, scat, abs, shift, g)
     }
+
+    /// Generate an optical environment for a given wavelength within the given grid voxel.
+    #[inline]
+    #[must_use]
+    pub fn voxel_env(&self, w: f64, index: [usize; 3]) -> Environment {
+        let env = self.env(w);
+
+        if let Some(volume) = &self.volume {
+            let (ref_index, scat, abs, g) = volume.voxel(
+                index,
+                (
+                    env.ref_index(),
+                    env.scat_coeff(),
+                    env.abs_coeff(),
+                    env.asym(),
+                ),
+            );
+            Environment::new(ref_index, scat, abs, env.shift_coeff(), g)
+        } else {
+            env
+        }
+    }
 }
 
 impl Display for Material {
@@ -104,10 +146,17 @@ impl Display for Material {
                 "m^-1"
             )?;
         }
-        display_field!(
+        if let Some(reemission) = &self.reemission {
+            display_field_ln!(fmt, "re-emission", reemission)?;
+        }
+        display_field_ln!(
             fmt,
             "asymmetry factor",
             self.asym_fact.y(DISPLAY_WAVELENGTH)
-        )
+        )?;
+        if let Some(volume) = &self.volume {
+            display_field_ln!(fmt, "volume", volume)?;
+        }
+        display_field!(fmt, "phase function", &self.phase)
     }
 }
@@ -1,16 +1,29 @@
 //! Monte-Carlo radiative transfer module.
 
+pub mod checkpoint;
+pub mod detector;
+pub mod diagnostics;
 pub mod environment;
 pub mod event;
 pub mod input;
+pub mod interface;
 pub mod life;
 pub mod light;
 pub mod material;
 pub mod output;
+pub mod phase;
 pub mod photon;
+pub mod polarisation;
+pub mod reemission;
 pub mod run;
 pub mod settings;
+pub mod stage;
+pub mod trajectory;
+pub mod uncertainty;
+pub mod volume;
 
 pub use self::{
-    environment::*, event::*, input::*, light::*, material::*, output::*, photon::*, settings::*,
+    checkpoint::*, detector::*, diagnostics::*, environment::*, event::*, input::*, interface::*,
+    light::*, material::*, output::*, phase::*, photon::*, polarisation::*, reemission::*,
+    settings::*, stage::*, trajectory::*, uncertainty::*, volume::*,
 };
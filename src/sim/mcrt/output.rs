@@ -1,16 +1,25 @@
 //! Output data structure.
 
 use crate::{
-    access, clone, display_field, display_field_ln, Aabb, Error, Histogram, Pos3, Save, X, Y, Z,
+    access, centres, clone, display_field, display_field_ln,
+    mcrt::{
+        fraction_below, save_obj, save_vtk, Detector, Diagnostics, Light, Settings, Trajectory,
+        Uncertainty,
+    },
+    put_variable, put_voxel_centres, Aabb, Binner, Error, Group, Histogram, Save, Set, Stokes, X,
+    Y, Z,
 };
-use ndarray::Array3;
+use ndarray::{Array3, Array4};
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fmt::{Display, Formatter},
     ops::AddAssign,
     path::Path,
 };
 
 /// Output data structure.
+#[derive(Deserialize, Serialize)]
 pub struct Output {
     /// Measured volume.
     boundary: Aabb,
@@ -20,32 +29,74 @@ pub struct Output {
     pub emitted_photons: Array3<f64>,
     /// Dist travelled by photons [m].
     pub dist_travelled: Array3<f64>,
-    /// Local energy [J].
+    /// Local unshifted light energy [J].
     pub energy: Array3<f64>,
-    /// Local absorptions [J].
+    /// Local absorbed power [W].
     pub absorptions: Array3<f64>,
-    /// Local shifts [J].
+    /// Local shifted power [W].
     pub shifts: Array3<f64>,
-    /// Tracked paths.
-    pub paths: Vec<Vec<Pos3>>,
-    /// Spectrometer.
-    pub spec: Histogram,
+    /// Local shifted light energy [J].
+    pub shifted_energy: Array3<f64>,
+    /// Local light energy [J] of each source, shifted or not.
+    /// Empty unless source tagging is enabled.
+    pub source_energy: BTreeMap<Group, Array3<f64>>,
+    /// Recorded photon paths.
+    pub paths: Vec<Trajectory>,
+    /// Detector wavelength spectra.
+    pub dets: BTreeMap<Group, Histogram>,
+    /// Optional time-of-flight binning.
+    time_bins: Option<Binner>,
+    /// Time-resolved local energy [J].
+    pub time_energy: Option<Array4<f64>>,
+    /// Time-resolved local absorbed power [W].
+    pub time_absorptions: Option<Array4<f64>>,
+    /// Time-resolved detector tallies.
+    /// Empty unless time binning is enabled.
+    pub time_dets: BTreeMap<Group, Histogram>,
+    /// Detector Stokes vectors, relative to the polariser axis.
+    /// Empty unless polarised transport is enabled.
+    pub det_stokes: BTreeMap<Group, Stokes>,
+    /// Optional batch-means uncertainty estimation.
+    pub uncertainty: Option<Uncertainty>,
+    /// Photon termination and event diagnostics.
+    pub diagnostics: Diagnostics,
+    /// Simulation settings, recorded as attributes of the saved data.
+    settings: BTreeMap<String, String>,
 }
 
 impl Output {
     access!(boundary, Aabb);
     clone!(cell_vol, f64);
+    access!(time_bins, Option<Binner>);
 
     /// Construct a new instance.
     #[inline]
     #[must_use]
-    pub fn new(boundary: Aabb, res: [usize; 3]) -> Self {
+    pub fn new(
+        boundary: Aabb,
+        res: [usize; 3],
+        sett: &Settings,
+        lights: &Set<Light>,
+        dets: &Set<Detector>,
+    ) -> Self {
         debug_assert!(res[X] > 0);
         debug_assert!(res[Y] > 0);
         debug_assert!(res[Z] > 0);
 
         let cell_vol = boundary.vol() / (res[X] * res[Y] * res[Z]) as f64;
 
+        let time_bins = sett.time_bins().clone();
+        let time_res = time_bins
+            .as_ref()
+            .map(|binner| [res[X], res[Y], res[Z], binner.bins() as usize]);
+
+        let time_dets = time_bins.as_ref().map_or_else(BTreeMap::new, |binner| {
+            dets.map()
+                .keys()
+                .map(|group| (group.clone(), Histogram::new_binner(binner.clone())))
+                .collect()
+        });
+
         Self {
             boundary,
             cell_vol,
@@ -54,9 +105,217 @@ impl Output {
             energy: Array3::zeros(res),
             absorptions: Array3::zeros(res),
             shifts: Array3::zeros(res),
+            shifted_energy: Array3::zeros(res),
+            source_energy: if sett.tag_sources() {
+                lights
+                    .map()
+                    .keys()
+                    .map(|group| (group.clone(), Array3::zeros(res)))
+                    .collect()
+            } else {
+                BTreeMap::new()
+            },
             paths: Vec::new(),
-            spec: Histogram::new(0e-9, 1000e-9, 100),
+            dets: dets
+                .map()
+                .iter()
+                .map(|(group, det)| (group.clone(), Histogram::new_binner(det.binner())))
+                .collect(),
+            time_energy: time_res.map(Array4::zeros),
+            time_absorptions: time_res.map(Array4::zeros),
+            time_dets,
+            det_stokes: if sett.polariser().is_some() {
+                dets.map()
+                    .keys()
+                    .map(|group| (group.clone(), Stokes::new_zero()))
+                    .collect()
+            } else {
+                BTreeMap::new()
+            },
+            uncertainty: sett.batches().map(|_| Uncertainty::new(res)),
+            diagnostics: Diagnostics::default(),
+            time_bins,
+            settings: sett.attributes(),
+        }
+    }
+
+    /// Close the current uncertainty estimation batch, if estimation is enabled.
+    #[inline]
+    pub fn end_batch(&mut self) {
+        if let Some(uncertainty) = &mut self.uncertainty {
+            uncertainty.end_batch(
+                &self.energy,
+                &self.absorptions,
+                &self.shifts,
+                &self.shifted_energy,
+            );
+        }
+    }
+
+    /// Determine the time bin corresponding to the given time of flight, if it is within the binning range.
+    #[inline]
+    #[must_use]
+    pub fn time_bin(&self, time: f64) -> Option<usize> {
+        self.time_bins
+            .as_ref()
+            .and_then(|binner| binner.try_bin(time))
+    }
+
+    /// Save the voxel data within a single NetCDF file.
+    /// Each tally is a named variable, with units, over coordinate variables of the voxel centres,
+    /// and the simulation settings and photon count are recorded as global attributes.
+    /// # Errors
+    /// if the file can not be created or written to.
+    #[inline]
+    fn save_netcdf(&self, path: &Path) -> Result<(), Error> {
+        let mut file = netcdf::create(path)?;
+
+        file.add_attribute("title", "MCRT output")?;
+        file.add_attribute("photons", self.diagnostics.emitted.num)?;
+        file.add_attribute("cell_volume", self.cell_vol)?;
+        for (name, value) in &self.settings {
+            file.add_attribute(name, value.as_str())?;
+        }
+
+        let dims = ["x", "y", "z"];
+        put_voxel_centres(&mut file, &self.boundary, self.energy.shape())?;
+
+        for (name, tally, units, long_name) in &[
+            (
+                "emission_dens",
+                &self.emitted_photons,
+                "m^-3",
+                "emitted photon weight density",
+            ),
+            (
+                "energy_dens",
+                &self.energy,
+                "J m^-3",
+                "light energy density",
+            ),
+            (
+                "absorption_dens",
+                &self.absorptions,
+                "W m^-3",
+                "absorbed power density",
+            ),
+            (
+                "shifted_dens",
+                &self.shifts,
+                "W m^-3",
+                "shifted power density",
+            ),
+            (
+                "shifted_energy_dens",
+                &self.shifted_energy,
+                "J m^-3",
+                "shifted light energy density",
+            ),
+        ] {
+            let dens = *tally / self.cell_vol;
+            put_variable(
+                &mut file,
+                name,
+                &dims,
+                dens.as_slice().ok_or("Missing slice data.")?,
+                units,
+                long_name,
+            )?;
+        }
+
+        put_variable(
+            &mut file,
+            "dist_travelled",
+            &dims,
+            self.dist_travelled
+                .as_slice()
+                .ok_or("Missing slice data.")?,
+            "m",
+            "distance travelled by photons",
+        )?;
+
+        for (group, energy) in &self.source_energy {
+            let dens = energy / self.cell_vol;
+            put_variable(
+                &mut file,
+                &format!("energy_dens_{}", group),
+                &dims,
+                dens.as_slice().ok_or("Missing slice data.")?,
+                "J m^-3",
+                &format!("light energy density of source {}", group),
+            )?;
+        }
+
+        if let Some(uncertainty) = &self.uncertainty {
+            let num_batches = uncertainty.num_batches();
+            for (name, moment, tally) in &[
+                ("energy_dens", &uncertainty.energy, &self.energy),
+                (
+                    "absorption_dens",
+                    &uncertainty.absorptions,
+                    &self.absorptions,
+                ),
+                ("shifted_dens", &uncertainty.shifts, &self.shifts),
+                (
+                    "shifted_energy_dens",
+                    &uncertainty.shifted_energy,
+                    &self.shifted_energy,
+                ),
+            ] {
+                let rel_err = moment.rel_err(tally, num_batches);
+                put_variable(
+                    &mut file,
+                    &format!("{}_rel_err", name),
+                    &dims,
+                    rel_err.as_slice().ok_or("Missing slice data.")?,
+                    "1",
+                    &format!("batch-means relative error of {}", name),
+                )?;
+            }
         }
+
+        if let Some(time_bins) = &self.time_bins {
+            let range = time_bins.range();
+            file.add_dimension("t", time_bins.bins() as usize)?;
+            put_variable(
+                &mut file,
+                "t",
+                &["t"],
+                &centres(range.min(), range.max(), time_bins.bins() as usize),
+                "s",
+                "time of flight bin centre",
+            )?;
+
+            let time_dims = ["x", "y", "z", "t"];
+            for (name, tally, units, long_name) in &[
+                (
+                    "energy_dens_time",
+                    &self.time_energy,
+                    "J m^-3",
+                    "time-resolved light energy density",
+                ),
+                (
+                    "absorption_dens_time",
+                    &self.time_absorptions,
+                    "W m^-3",
+                    "time-resolved absorbed power density",
+                ),
+            ] {
+                if let Some(tally) = tally {
+                    let dens = tally / self.cell_vol;
+                    put_variable(
+                        &mut file,
+                        name,
+                        &time_dims,
+                        dens.as_slice().ok_or("Missing slice data.")?,
+                        units,
+                        long_name,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -68,7 +327,45 @@ impl AddAssign<Self> for Output {
         self.energy += &rhs.energy;
         self.absorptions += &rhs.absorptions;
         self.shifts += &rhs.shifts;
+        self.shifted_energy += &rhs.shifted_energy;
+        for (group, energy) in &mut self.source_energy {
+            if let Some(rhs_energy) = rhs.source_energy.get(group) {
+                *energy += rhs_energy;
+            }
+        }
         self.paths.append(&mut rhs.paths);
+        for (group, hist) in &mut self.dets {
+            if let Some(rhs_hist) = rhs.dets.get(group) {
+                *hist += rhs_hist;
+            }
+        }
+
+        if let (Some(time_energy), Some(rhs_time_energy)) =
+            (&mut self.time_energy, &rhs.time_energy)
+        {
+            *time_energy += rhs_time_energy;
+        }
+        if let (Some(time_absorptions), Some(rhs_time_absorptions)) =
+            (&mut self.time_absorptions, &rhs.time_absorptions)
+        {
+            *time_absorptions += rhs_time_absorptions;
+        }
+        for (group, hist) in &mut self.time_dets {
+            if let Some(rhs_hist) = rhs.time_dets.get(group) {
+                *hist += rhs_hist;
+            }
+        }
+        for (group, stokes) in &mut self.det_stokes {
+            if let Some(rhs_stokes) = rhs.det_stokes.get(group) {
+                *stokes += rhs_stokes;
+            }
+        }
+        if let (Some(uncertainty), Some(rhs_uncertainty)) =
+            (&mut self.uncertainty, &rhs.uncertainty)
+        {
+            *uncertainty += rhs_uncertainty;
+        }
+        self.diagnostics += &rhs.diagnostics;
     }
 }
 
@@ -84,41 +381,100 @@ impl Display for Output {
             "m"
         )?;
         display_field_ln!(fmt, "total energy", self.energy.sum(), "J")?;
-        display_field_ln!(fmt, "total absorption energy", self.absorptions.sum(), "J")?;
-        display_field_ln!(fmt, "total shifted energy", self.shifts.sum(), "J")?;
-        display_field!(fmt, "number of recorded paths", self.paths.len())
+        display_field_ln!(fmt, "total absorbed power", self.absorptions.sum(), "W")?;
+        display_field_ln!(fmt, "total shifted power", self.shifts.sum(), "W")?;
+        display_field_ln!(
+            fmt,
+            "total shifted light energy",
+            self.shifted_energy.sum(),
+            "J"
+        )?;
+        for (group, energy) in &self.source_energy {
+            display_field_ln!(fmt, &format!("source {} energy", group), energy.sum(), "J")?;
+        }
+        display_field_ln!(fmt, "number of recorded paths", self.paths.len())?;
+        for (group, hist) in &self.dets {
+            display_field_ln!(
+                fmt,
+                &format!("detector {} total", group),
+                hist.counts().sum()
+            )?;
+        }
+        for (group, stokes) in &self.det_stokes {
+            display_field_ln!(
+                fmt,
+                &format!("detector {} degree of polarisation", group),
+                stokes.degree()
+            )?;
+        }
+        if let Some(uncertainty) = &self.uncertainty {
+            let num_batches = uncertainty.num_batches();
+            display_field_ln!(fmt, "uncertainty batches", num_batches)?;
+            for (name, moment, tally) in &[
+                ("energy", &uncertainty.energy, &self.energy),
+                ("absorption", &uncertainty.absorptions, &self.absorptions),
+                ("shift", &uncertainty.shifts, &self.shifts),
+                (
+                    "shifted energy",
+                    &uncertainty.shifted_energy,
+                    &self.shifted_energy,
+                ),
+            ] {
+                let rel_err = moment.rel_err(tally, num_batches);
+                display_field_ln!(
+                    fmt,
+                    &format!("{} voxels under 5% error", name),
+                    100.0 * fraction_below(&rel_err, 0.05),
+                    "%"
+                )?;
+            }
+        }
+        if let Some(time_bins) = &self.time_bins {
+            display_field_ln!(fmt, "time bins", time_bins, "s")?;
+        } else {
+            display_field_ln!(fmt, "time bins", "none")?;
+        }
+        display_field!(fmt, "diagnostics", &self.diagnostics)
     }
 }
 
 impl Save for Output {
     #[inline]
     fn save(&self, out_dir: &Path) -> Result<(), Error> {
-        let path = out_dir.join("emission_dens.nc");
+        let path = out_dir.join("mcrt.nc");
         println!("saving: {}", path.display());
-        let emission_dens = &self.emitted_photons / self.cell_vol;
-        emission_dens.save(&path)?;
+        self.save_netcdf(&path)?;
 
-        let path = out_dir.join("energy_dens.nc");
-        println!("saving: {}", path.display());
-        let energy_dens = &self.energy / self.cell_vol;
-        energy_dens.save(&path)?;
+        for (group, hist) in &self.dets {
+            let path = out_dir.join(format!("detector_{}.csv", group));
+            println!("saving: {}", path.display());
+            hist.save(&path)?;
+        }
 
-        let path = out_dir.join("absorption_dens.nc");
-        println!("saving: {}", path.display());
-        let absorption_dens = &self.absorptions / self.cell_vol;
-        absorption_dens.save(&path)?;
+        for (group, hist) in &self.time_dets {
+            let path = out_dir.join(format!("detector_{}_time.csv", group));
+            println!("saving: {}", path.display());
+            hist.save(&path)?;
+        }
 
-        let path = out_dir.join("shifted_dens.nc");
-        println!("saving: {}", path.display());
-        let shifted_dens = &self.shifts / self.cell_vol;
-        shifted_dens.save(&path)?;
+        for (group, stokes) in &self.det_stokes {
+            let path = out_dir.join(format!("detector_{}_stokes.csv", group));
+            println!("saving: {}", path.display());
+            stokes.save(&path)?;
+        }
 
-        let path = out_dir.join("dist_travelled.nc");
-        println!("saving: {}", path.display());
-        self.dist_travelled.save(&path)?;
+        self.diagnostics.save(&out_dir.join("diagnostics.json"))?;
 
-        let path = out_dir.join("spectrometer.csv");
-        println!("saving: {}", path.display());
-        self.spec.save(&path)
+        if !self.paths.is_empty() {
+            let path = out_dir.join("paths.obj");
+            println!("saving: {}", path.display());
+            save_obj(&self.paths, &path)?;
+
+            let path = out_dir.join("paths.vtk");
+            println!("saving: {}", path.display());
+            save_vtk(&self.paths, &path)?;
+        }
+
+        Ok(())
     }
 }
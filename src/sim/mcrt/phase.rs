@@ -0,0 +1,259 @@
+//! Phase function implementation.
+
+use crate::{distribution, Formula, Mueller};
+use ndarray::Array1;
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result},
+};
+
+/// Number of bisections used to invert a tabulated distribution.
+const BISECTIONS: usize = 48;
+
+/// Cumulative probability step used to differentiate a tabulated distribution.
+const DIFF_STEP: f64 = 1.0e-6;
+
+/// Scattering phase function enumeration.
+pub enum Phase {
+    /// Henyey-Greenstein, using the material asymmetry factor.
+    HenyeyGreenstein,
+    /// Two-term Henyey-Greenstein, blending the forward material asymmetry factor with a backward lobe.
+    TwoTermHenyeyGreenstein {
+        /// Backward asymmetry factor.
+        back: Formula,
+        /// Forward lobe fraction.
+        frac: Formula,
+    },
+    /// Modified Henyey-Greenstein, blending the material asymmetry factor with an isotropic cos^2 term.
+    ModifiedHenyeyGreenstein {
+        /// Henyey-Greenstein fraction.
+        frac: Formula,
+    },
+    /// Rayleigh.
+    Rayleigh,
+    /// Tabulated phase functions.
+    Tabulated {
+        /// Ascending tabulation wavelengths [m].
+        wavelengths: Array1<f64>,
+        /// Inverse cumulative distribution functions, mapping a uniform variate to a scattering angle [rad].
+        inv_cdfs: Vec<Formula>,
+    },
+}
+
+impl Phase {
+    /// Construct a new two-term Henyey-Greenstein instance.
+    #[inline]
+    #[must_use]
+    pub const fn new_two_term_henyey_greenstein(back: Formula, frac: Formula) -> Self {
+        Self::TwoTermHenyeyGreenstein { back, frac }
+    }
+
+    /// Construct a new modified Henyey-Greenstein instance.
+    #[inline]
+    #[must_use]
+    pub const fn new_modified_henyey_greenstein(frac: Formula) -> Self {
+        Self::ModifiedHenyeyGreenstein { frac }
+    }
+
+    /// Construct a new tabulated instance from phase functions p(theta) sampled at each wavelength.
+    /// Scattering angles are given in radians.
+    #[inline]
+    #[must_use]
+    pub fn new_tabulated(wavelengths: Array1<f64>, tables: &[(Array1<f64>, Array1<f64>)]) -> Self {
+        debug_assert!(!wavelengths.is_empty());
+        debug_assert!(wavelengths.len() == tables.len());
+
+        let inv_cdfs = tables
+            .iter()
+            .map(|(thetas, ps)| distribution::polar_inv_cdf(thetas, ps))
+            .collect();
+
+        Self::Tabulated {
+            wavelengths,
+            inv_cdfs,
+        }
+    }
+
+    /// Sample a scattering angle [rad] for a given wavelength [m] and material asymmetry factor.
+    #[inline]
+    #[must_use]
+    pub fn gen<R: Rng>(&self, rng: &mut R, w: f64, asym: f64) -> f64 {
+        match self {
+            Self::HenyeyGreenstein => distribution::henyey_greenstein(rng, asym),
+            Self::TwoTermHenyeyGreenstein { back, frac } => {
+                if rng.gen::<f64>() < frac.y(w) {
+                    distribution::henyey_greenstein(rng, asym)
+                } else {
+                    distribution::henyey_greenstein(rng, back.y(w))
+                }
+            }
+            Self::ModifiedHenyeyGreenstein { frac } => {
+                if rng.gen::<f64>() < frac.y(w) {
+                    distribution::henyey_greenstein(rng, asym)
+                } else {
+                    rng.gen_range(-1.0_f64, 1.0).cbrt().acos()
+                }
+            }
+            Self::Rayleigh => distribution::rayleigh(rng),
+            Self::Tabulated {
+                wavelengths,
+                inv_cdfs,
+            } => {
+                let index = Self::select_table(rng, wavelengths, w);
+                inv_cdfs[index].y(rng.gen())
+            }
+        }
+    }
+
+    /// Evaluate the probability density, per unit solid angle [1/sr], of scattering through an angle with the given cosine,
+    /// for a given wavelength [m] and material asymmetry factor.
+    /// The density is that from which `gen` samples.
+    #[inline]
+    #[must_use]
+    pub fn pdf(&self, w: f64, asym: f64, cos_theta: f64) -> f64 {
+        debug_assert!(cos_theta.abs() <= 1.0);
+
+        match self {
+            Self::HenyeyGreenstein => henyey_greenstein_pdf(asym, cos_theta),
+            Self::TwoTermHenyeyGreenstein { back, frac } => {
+                let f = frac.y(w);
+                f.mul_add(
+                    henyey_greenstein_pdf(asym, cos_theta),
+                    (1.0 - f) * henyey_greenstein_pdf(back.y(w), cos_theta),
+                )
+            }
+            Self::ModifiedHenyeyGreenstein { frac } => {
+                let f = frac.y(w);
+                f.mul_add(
+                    henyey_greenstein_pdf(asym, cos_theta),
+                    (1.0 - f) * 3.0 * cos_theta.powi(2) / (4.0 * PI),
+                )
+            }
+            Self::Rayleigh => 3.0 * cos_theta.mul_add(cos_theta, 1.0) / (16.0 * PI),
+            Self::Tabulated {
+                wavelengths,
+                inv_cdfs,
+            } => {
+                let theta = cos_theta.acos();
+                let (lower, upper, t) = Self::bracket_table(wavelengths, w);
+                t.mul_add(
+                    tabulated_pdf(&inv_cdfs[upper], theta),
+                    (1.0 - t) * tabulated_pdf(&inv_cdfs[lower], theta),
+                )
+            }
+        }
+    }
+
+    /// Construct the scattering matrix for a given wavelength [m], material asymmetry factor, and scattering angle cosine.
+    /// The intensity term is the phase function density [1/sr], so the polarised phase function shares the polar distribution `gen` samples.
+    /// Rayleigh scattering uses the dipole matrix.
+    /// Other phase functions follow the polarised Henyey-Greenstein model of Liu and Weng (2006),
+    /// with complete linear polarisation at right angles and no circular polarisation,
+    /// scaling the dipole polarisation and retention ratios by their own density.
+    #[inline]
+    #[must_use]
+    pub fn mueller(&self, w: f64, asym: f64, cos_theta: f64) -> Mueller {
+        let pdf = self.pdf(w, asym, cos_theta);
+        let dipole = Mueller::new_rayleigh(cos_theta);
+
+        Mueller::new(
+            pdf,
+            pdf * dipole.s12(),
+            pdf * dipole.s33(),
+            pdf * dipole.s34(),
+        )
+    }
+
+    /// Select a table to sample from.
+    /// Wavelengths between tabulations choose either neighbour with a probability weighted by proximity.
+    #[inline]
+    #[must_use]
+    fn select_table<R: Rng>(rng: &mut R, wavelengths: &Array1<f64>, w: f64) -> usize {
+        let (lower, upper, t) = Self::bracket_table(wavelengths, w);
+
+        if rng.gen::<f64>() < t {
+            upper
+        } else {
+            lower
+        }
+    }
+
+    /// Determine the tables neighbouring a wavelength, and the fractional distance between them.
+    /// Wavelengths outside of the tabulated range use the nearest table alone.
+    #[inline]
+    #[must_use]
+    fn bracket_table(wavelengths: &Array1<f64>, w: f64) -> (usize, usize, f64) {
+        let last = wavelengths.len() - 1;
+        if w <= wavelengths[0] {
+            return (0, 0, 0.0);
+        }
+        if w >= wavelengths[last] {
+            return (last, last, 0.0);
+        }
+
+        let upper = wavelengths
+            .iter()
+            .position(|wn| *wn > w)
+            .expect("Could not locate wavelength table.");
+        let lower = upper - 1;
+        let t = (w - wavelengths[lower]) / (wavelengths[upper] - wavelengths[lower]);
+
+        (lower, upper, t)
+    }
+}
+
+/// Evaluate the Henyey-Greenstein probability density per unit solid angle [1/sr].
+#[inline]
+#[must_use]
+fn henyey_greenstein_pdf(asym: f64, cos_theta: f64) -> f64 {
+    let denom = (-2.0 * asym).mul_add(cos_theta, asym.mul_add(asym, 1.0));
+
+    (1.0 - asym.powi(2)) / (4.0 * PI * denom.powf(1.5))
+}
+
+/// Evaluate the probability density per unit solid angle [1/sr] sampled by a polar inverse cumulative distribution function.
+/// The density is found from the gradient of the inverse function at the given angle [rad].
+#[inline]
+#[must_use]
+fn tabulated_pdf(inv_cdf: &Formula, theta: f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1.0);
+    if theta < inv_cdf.y(lo) || theta > inv_cdf.y(hi) {
+        return 0.0;
+    }
+
+    // Invert the inverse distribution by bisection to locate the cumulative probability.
+    for _ in 0..BISECTIONS {
+        let mid = 0.5 * (lo + hi);
+        if inv_cdf.y(mid) < theta {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    let u = 0.5 * (lo + hi);
+    let du = DIFF_STEP.min(u).min(1.0 - u).max(DIFF_STEP * 1.0e-3);
+    let (u_minus, u_plus) = ((u - du).max(0.0), (u + du).min(1.0));
+    let dtheta = inv_cdf.y(u_plus) - inv_cdf.y(u_minus);
+    if dtheta <= 0.0 || theta.sin() <= 0.0 {
+        return 0.0;
+    }
+
+    (u_plus - u_minus) / (dtheta * 2.0 * PI * theta.sin())
+}
+
+impl Display for Phase {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let kind = match self {
+            Self::HenyeyGreenstein => "Henyey-Greenstein",
+            Self::TwoTermHenyeyGreenstein { .. } => "Two-Term Henyey-Greenstein",
+            Self::ModifiedHenyeyGreenstein { .. } => "Modified Henyey-Greenstein",
+            Self::Rayleigh => "Rayleigh",
+            Self::Tabulated { .. } => "Tabulated",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
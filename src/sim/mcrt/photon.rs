@@ -1,6 +1,8 @@
 //! Photon implementation.
 
-use crate::{access, clone, Ray};
+use crate::{access, clone, mcrt::Polarisation, Mueller, Ray};
+use rand::Rng;
+use std::f64::consts::PI;
 
 /// Photon structure.
 pub struct Photon {
@@ -12,13 +14,25 @@ pub struct Photon {
     wavelength: f64,
     /// Power [J/s].
     power: f64,
+    /// Time of flight [s].
+    time: f64,
+    /// Shifted flag.
+    shifted: bool,
+    /// Optional polarisation state.
+    pol: Option<Polarisation>,
+    /// Optional index of the emitting source.
+    source: Option<usize>,
 }
 
 impl Photon {
     access!(ray, ray_mut, Ray);
     clone!(weight, weight_mut, f64);
     clone!(wavelength, wavelength_mut, f64);
-    clone!(power, f64);
+    clone!(power, power_mut, f64);
+    clone!(time, time_mut, f64);
+    clone!(shifted, shifted_mut, bool);
+    access!(pol, pol_mut, Option<Polarisation>);
+    clone!(source, source_mut, Option<usize>);
 
     /// Construct a new instance.
     #[inline]
@@ -32,6 +46,29 @@ impl Photon {
             weight: 1.0,
             wavelength,
             power,
+            time: 0.0,
+            shifted: false,
+            pol: None,
+            source: None,
+        }
+    }
+
+    /// Calculate the energy [J] carried by the weighted photon over a unit emission time.
+    #[inline]
+    #[must_use]
+    pub fn energy(&self) -> f64 {
+        self.weight * self.power
+    }
+
+    /// Rotate the photon through a pitch [rad] sampled from the phase function, and a random roll.
+    /// Polarised photons sample their roll from the polarised phase function of the given scattering matrix,
+    /// and carry their polarisation state with them.
+    #[inline]
+    pub fn scatter<R: Rng>(&mut self, rng: &mut R, pitch: f64, mueller: Option<&Mueller>) {
+        if let (Some(pol), Some(mueller)) = (&mut self.pol, mueller) {
+            pol.scatter(rng, &mut self.ray, pitch, mueller);
+        } else {
+            self.ray.rotate(pitch, rng.gen_range(0.0, 2.0 * PI));
         }
     }
 }
@@ -0,0 +1,200 @@
+//! Polarisation implementation.
+
+use crate::{access, display_field, display_field_ln, Dir3, Mueller, Pos3, Ray, Stokes, Vec3};
+use attr::load;
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter, Result},
+};
+
+/// Polarised emission settings.
+/// Enables polarised photon transport when present.
+#[load]
+pub struct Polariser {
+    /// Emitted Stokes vector.
+    stokes: Stokes,
+    /// Global reference axis, projected perpendicular to each photon's direction of travel.
+    axis: Vec3,
+}
+
+impl Polariser {
+    access!(stokes, Stokes);
+    access!(axis, Vec3);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(stokes: Stokes, axis: Vec3) -> Self {
+        debug_assert!(axis.magnitude() > 0.0);
+
+        Self { stokes, axis }
+    }
+
+    /// Generate the polarisation state of a photon emitted in the given direction.
+    #[inline]
+    #[must_use]
+    pub fn polarise(&self, dir: &Dir3) -> Polarisation {
+        let mut stokes = self.stokes;
+        stokes.normalise();
+
+        Polarisation::new(stokes, frame(dir, &self.axis))
+    }
+
+    /// Determine the Stokes vector of a photon with respect to the reference axis.
+    #[inline]
+    #[must_use]
+    pub fn measure(&self, dir: &Dir3, pol: &Polarisation) -> Stokes {
+        let mut stokes = *pol.stokes();
+        stokes.rotate(angle(dir, pol.frame(), &frame(dir, &self.axis)));
+
+        stokes
+    }
+}
+
+/// Photon polarisation state.
+pub struct Polarisation {
+    /// Unit intensity Stokes vector.
+    stokes: Stokes,
+    /// Reference frame, perpendicular to the direction of travel.
+    frame: Dir3,
+}
+
+impl Polarisation {
+    access!(stokes, Stokes);
+    access!(frame, Dir3);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(stokes: Stokes, frame: Dir3) -> Self {
+        debug_assert!((stokes.i() - 1.0).abs() < 1.0e-6);
+
+        Self { stokes, frame }
+    }
+
+    /// Construct an unpolarised instance for the given direction of travel.
+    #[inline]
+    #[must_use]
+    pub fn new_unpolarised(dir: &Dir3) -> Self {
+        Self::new(Stokes::new_unpolarised(), frame(dir, &Vec3::z()))
+    }
+
+    /// Scatter the photon through a pitch [rad] sampled from the phase function,
+    /// transforming the polarisation state with the scattering matrix evaluated at that pitch.
+    /// The roll is sampled from the polarised phase function given the pitch,
+    /// so the photon weight is unchanged and the Stokes vector is rescaled to unit intensity.
+    #[inline]
+    pub fn scatter<R: Rng>(&mut self, rng: &mut R, ray: &mut Ray, pitch: f64, mueller: &Mueller) {
+        debug_assert!(mueller.s11() > 0.0);
+
+        // Rolls are accepted in proportion to the scattered intensity.
+        let ratio = mueller.s12() / mueller.s11();
+        let bound = ratio
+            .abs()
+            .mul_add(self.stokes.q().hypot(self.stokes.u()), 1.0);
+        let roll = loop {
+            let roll = rng.gen_range(0.0, 2.0 * PI);
+            let mut stokes = self.stokes;
+            stokes.rotate(roll);
+            if rng.gen_range(0.0, bound) <= ratio.mul_add(stokes.q(), stokes.i()) {
+                break roll;
+            }
+        };
+
+        self.turn(ray, pitch, roll, mueller);
+    }
+
+    /// Determine the polarisation state of a photon were it scattered from one direction of travel into another,
+    /// and the polarised phase function density [1/sr] of that scattering.
+    #[inline]
+    #[must_use]
+    pub fn scattered(&self, dir: &Dir3, next_dir: &Dir3, mueller: &Mueller) -> (Self, f64) {
+        let mut pol = Self::new(self.stokes, self.frame);
+        pol.align(dir, next_dir);
+        let density = mueller.intensity(&pol.stokes);
+
+        let mut ray = Ray::new(Pos3::origin(), *dir);
+        pol.turn(
+            &mut ray,
+            dir.dot(next_dir).max(-1.0).min(1.0).acos(),
+            0.0,
+            mueller,
+        );
+
+        (pol, density)
+    }
+
+    /// Rotate the photon with a given pitch and subsequent roll manoeuvre,
+    /// transforming the polarisation state with a scattering matrix within the scattering plane.
+    #[inline]
+    fn turn(&mut self, ray: &mut Ray, pitch: f64, roll: f64, mueller: &Mueller) {
+        self.stokes.rotate(roll);
+        ray.rotate_frame(pitch, roll, &mut self.frame);
+
+        self.stokes = mueller.apply(&self.stokes);
+        self.stokes.normalise();
+    }
+
+    /// Rotate the reference frame to lie within the plane of incidence of a surface.
+    /// Normal incidence leaves the frame unchanged.
+    #[inline]
+    pub fn align(&mut self, dir: &Dir3, norm: &Dir3) {
+        let perp = dir.cross(norm);
+        if perp.magnitude() < 1.0e-9 {
+            return;
+        }
+
+        let plane = Dir3::new_normalize(perp.cross(dir));
+        self.stokes.rotate(angle(dir, &self.frame, &plane));
+        self.frame = plane;
+    }
+
+    /// Apply an interface Mueller matrix, within the aligned frame, and carry the frame onto the outgoing direction.
+    #[inline]
+    pub fn cross(&mut self, mueller: &Mueller, dir: &Dir3, next_dir: &Dir3) {
+        let perp = dir.cross(&self.frame);
+        self.frame = Dir3::new_normalize(perp.cross(next_dir));
+
+        self.stokes = mueller.apply(&self.stokes);
+        self.stokes.normalise();
+    }
+}
+
+/// Project an axis perpendicular to a direction of travel to form a reference frame.
+/// Falls back to an alternative axis if the axis is parallel to the direction.
+#[inline]
+#[must_use]
+fn frame(dir: &Dir3, axis: &Vec3) -> Dir3 {
+    let proj = axis - (dir.as_ref() * dir.dot(axis));
+    if proj.magnitude() > (axis.magnitude() * 1.0e-6) {
+        return Dir3::new_normalize(proj);
+    }
+
+    let alt = if dir.z.abs() < 0.9 {
+        Vec3::z()
+    } else {
+        Vec3::x()
+    };
+    Dir3::new_normalize(alt - (dir.as_ref() * dir.dot(&alt)))
+}
+
+/// Calculate the anticlockwise angle, looking into the direction of travel, from one reference frame to another.
+#[inline]
+#[must_use]
+fn angle(dir: &Dir3, from: &Dir3, to: &Dir3) -> f64 {
+    from.cross(to).dot(dir).atan2(from.dot(to))
+}
+
+impl Display for Polariser {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "stokes", &self.stokes)?;
+        display_field!(
+            fmt,
+            "reference axis",
+            format!("[{}, {}, {}]", self.axis.x, self.axis.y, self.axis.z)
+        )
+    }
+}
@@ -0,0 +1,50 @@
+//! Re-emission implementation.
+
+use crate::{access, clone, display_field, display_field_ln, Probability};
+use std::fmt::{Display, Formatter, Result};
+
+/// Inelastic re-emission structure.
+/// Describes the light emitted following a fluorescence or Raman shift event.
+pub struct Reemission {
+    /// Emission spectrum.
+    spec: Probability,
+    /// Quantum yield.
+    quantum_yield: f64,
+    /// Optional emission lifetime [s].
+    lifetime: Option<f64>,
+}
+
+impl Reemission {
+    access!(spec, Probability);
+    clone!(quantum_yield, f64);
+    clone!(lifetime, Option<f64>);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(spec: Probability, quantum_yield: f64, lifetime: Option<f64>) -> Self {
+        debug_assert!(quantum_yield >= 0.0);
+        debug_assert!(quantum_yield <= 1.0);
+        debug_assert!(lifetime.map_or(true, |t| t > 0.0));
+
+        Self {
+            spec,
+            quantum_yield,
+            lifetime,
+        }
+    }
+}
+
+impl Display for Reemission {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "spectrum", &self.spec)?;
+        if let Some(lifetime) = self.lifetime {
+            display_field_ln!(fmt, "quantum yield", self.quantum_yield)?;
+            display_field!(fmt, "lifetime", lifetime, "s")
+        } else {
+            display_field!(fmt, "quantum yield", self.quantum_yield)
+        }
+    }
+}
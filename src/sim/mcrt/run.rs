@@ -1,26 +1,72 @@
 //! Simulation run functions.
 
 use crate::{
-    mcrt::{life::Life, Input, Output},
+    hash_unit,
+    mcrt::{check_batches, check_spectra, life::Life, Checkpoint, Input, Output, Recording},
     Bar, Error,
 };
-use rand::thread_rng;
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use std::{
+    fs::create_dir_all,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 /// Run an MCRT simulation.
+/// Each thread draws from its own stream of the master seed,
+/// so a given seed and thread count always reproduces the same output.
+/// If a checkpoint interval is set, each thread periodically records its progress within the checkpoint directory.
+/// If a resume directory is given, threads continue from the checkpoints found there.
 /// # Errors
-/// if the progress bar can not be locked.
+/// if the number of threads is zero,
+/// there are no lights,
+/// a light emits nothing within the wavelength range,
+/// the photons can not be divided evenly into uncertainty batches,
+/// the progress bar can not be locked,
+/// or a checkpoint can not be read or written.
 #[inline]
-pub fn simulate(input: &Input, func: Life) -> Result<Output, Error> {
+pub fn simulate(
+    input: &Input,
+    func: Life,
+    check_dir: &Path,
+    resume_dir: Option<&Path>,
+) -> Result<Output, Error> {
+    if input.sett.threads() == Some(0) {
+        return Err(Error::Text(
+            "Number of threads must be positive.".to_string(),
+        ));
+    }
+    if input.lights.map().is_empty() {
+        return Err(Error::Text("At least one light is required.".to_string()));
+    }
+    check_spectra(input.lights, input.sett.range())?;
+
+    if let Some(batches) = input.sett.batches() {
+        check_batches(input.sett.num_phot(), input.sett.threads(), batches)?;
+    }
+    let num_threads = input.sett.num_threads();
+
+    if input.sett.checkpoint().is_some() {
+        create_dir_all(check_dir)?;
+    }
+
     let pb = Bar::new("Randomising", input.sett.num_phot());
     let pb = Arc::new(Mutex::new(pb));
 
-    // let threads: Vec<usize> = (0..num_cpus::get()).collect(); // Multi-thread.
-    let threads = vec![0]; // Single thread.
+    let threads: Vec<usize> = (0..num_threads).collect();
     let mut data: Vec<_> = threads
         .par_iter()
-        .map(|id| single_thread(*id, &Arc::clone(&pb), input, func))
+        .map(|id| {
+            single_thread(
+                *id,
+                num_threads,
+                &Arc::clone(&pb),
+                input,
+                func,
+                check_dir,
+                resume_dir,
+            )
+        })
         .collect();
     pb.lock()?.finish_with_message("Render complete");
 
@@ -34,28 +80,109 @@ pub fn simulate(input: &Input, func: Life) -> Result<Output, Error> {
 
 /// Simulate with a single thread.
 /// # Errors
-/// if the progress bar can not be locked.
+/// if the progress bar can not be locked,
+/// or a checkpoint can not be read or written.
 #[inline]
 fn single_thread(
-    _thread_id: usize,
+    thread_id: usize,
+    num_threads: usize,
     pb: &Arc<Mutex<Bar>>,
     input: &Input,
     func: Life,
+    check_dir: &Path,
+    resume_dir: Option<&Path>,
 ) -> Result<Output, Error> {
-    let mut data = Output::new(input.grid.boundary().clone(), *input.grid.res());
-
-    let mut rng = thread_rng();
-
-    while let Some((start, end)) = {
-        let mut pb = pb.lock()?;
-        let b = pb.block(input.sett.block_size());
-        std::mem::drop(pb);
-        b
-    } {
-        for _ in start..end {
-            func(input, &mut data, &mut rng);
+    let seed = input.sett.seed();
+
+    let num_phot = thread_phot(input.sett.num_phot(), num_threads, thread_id);
+
+    // Uncertainty batches end at fixed photon counts, so resumed runs close the same batches.
+    let batch_size = input.sett.batches().map(|batches| {
+        debug_assert!(batches > 0 && num_phot % batches == 0);
+        num_phot / batches
+    });
+
+    // Threads without a checkpoint to resume from start afresh.
+    let res = *input.grid.res();
+    let mut check = match resume_dir {
+        Some(resume_dir) if Checkpoint::path(resume_dir, thread_id).exists() => {
+            Checkpoint::load(resume_dir, seed, num_threads, thread_id, res, batch_size)?
         }
+        _ => Checkpoint::new(
+            seed,
+            num_threads,
+            thread_id,
+            res,
+            batch_size,
+            Output::new(
+                input.grid.boundary().clone(),
+                res,
+                input.sett,
+                input.lights,
+                input.dets,
+            ),
+        ),
+    };
+    let mut rng = check.rng();
+
+    if check.count() > num_phot {
+        return Err(Error::Text(format!(
+            "Thread {} checkpoint has already simulated more than the {} photons requested.",
+            thread_id, num_phot
+        )));
     }
+    pb.lock()?.inc(check.count());
+
+    let mut since_check = 0;
+    while check.count() < num_phot {
+        let mut block = input.sett.block_size().min(num_phot - check.count());
+        if let Some(batch_size) = batch_size {
+            block = block.min(batch_size - (check.count() % batch_size));
+        }
+        for index in check.count()..(check.count() + block) {
+            // Recording depends only on the photon index, leaving the generator stream untouched.
+            let record = match input.sett.paths() {
+                None => false,
+                Some(Recording::First(n)) => index < thread_phot(*n, num_threads, thread_id),
+                Some(Recording::Sample(prob)) => hash_unit(seed, thread_id as u64, index) < *prob,
+            };
+            func(input, check.data_mut(), &mut rng, record);
+        }
+        check.advance(block);
+
+        if let Some(batch_size) = batch_size {
+            if check.count() % batch_size == 0 || check.count() == num_phot {
+                check.data_mut().end_batch();
+            }
+        }
+
+        pb.lock()?.inc(block);
+
+        if let Some(interval) = input.sett.checkpoint() {
+            since_check += block;
+            if since_check >= interval {
+                check.save(check_dir, &rng)?;
+                since_check = 0;
+            }
+        }
+    }
+
+    // Record the finished thread so that a resumed run need not repeat it.
+    if input.sett.checkpoint().is_some() {
+        check.save(check_dir, &rng)?;
+    }
+
+    Ok(check.into_data())
+}
+
+/// Determine the number of photons allocated to the given thread.
+#[inline]
+#[must_use]
+fn thread_phot(num_phot: u64, num_threads: usize, thread_id: usize) -> u64 {
+    debug_assert!(thread_id < num_threads);
+
+    let num_threads = num_threads as u64;
+    let thread_id = thread_id as u64;
 
-    Ok(data)
+    (num_phot / num_threads) + u64::from(thread_id < (num_phot % num_threads))
 }
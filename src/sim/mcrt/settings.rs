@@ -1,8 +1,15 @@
 //! Settings implementation.
 
-use crate::{access, clone, display_field, display_field_ln, Group, Range};
+use crate::{
+    access, clone, display_field, display_field_ln,
+    mcrt::{Polariser, Recording},
+    Binner, Group, Range, X, Y, Z,
+};
 use attr::load;
-use std::fmt::{Display, Formatter, Result};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result},
+};
 
 /// MCRT settings structure.
 #[load]
@@ -11,6 +18,15 @@ pub struct Settings {
     block_size: u64,
     /// Number of photons to simulate.
     num_phot: u64,
+    /// Master random number seed.
+    seed: u64,
+    /// Optional number of threads to use, defaulting to the number of cpus.
+    threads: Option<usize>,
+    /// Optional number of photons each thread simulates between checkpoints.
+    checkpoint: Option<u64>,
+    /// Optional number of batches each thread divides its photons into for uncertainty estimation.
+    /// Requires an explicit number of threads, and the number of photons must be a multiple of the number of threads and batches.
+    batches: Option<u64>,
     /// Bump distance [m].
     bump_dist: f64,
     /// Loop limit.
@@ -23,17 +39,118 @@ pub struct Settings {
     init_mat: Group,
     /// Wavelength limits.
     range: Range,
+    /// Periodic grid boundary axes.
+    periodic: [bool; 3],
+    /// Optional time-of-flight binning [s].
+    time_bins: Option<Binner>,
+    /// Optional polarised emission, enabling polarised transport.
+    polariser: Option<Polariser>,
+    /// Optional flag to tally the light energy of each source separately, defaulting to false.
+    tag_sources: Option<bool>,
+    /// Optional selection of photon paths to record.
+    paths: Option<Recording>,
 }
 
 impl Settings {
     clone!(block_size, u64);
     clone!(num_phot, u64);
+    clone!(seed, u64);
+    clone!(threads, Option<usize>);
+    clone!(checkpoint, Option<u64>);
+    clone!(batches, Option<u64>);
     clone!(bump_dist, f64);
     clone!(loop_limit, u64);
     clone!(roulette_weight, f64);
     clone!(roulette_barrels, u64);
     access!(init_mat, Group);
     access!(range, Range);
+    access!(periodic, [bool; 3]);
+    access!(time_bins, Option<Binner>);
+    access!(polariser, Option<Polariser>);
+    access!(paths, Option<Recording>);
+
+    /// Determine if photons are tagged with their source.
+    #[inline]
+    #[must_use]
+    pub fn tag_sources(&self) -> bool {
+        self.tag_sources.unwrap_or(false)
+    }
+
+    /// Determine the number of threads to use.
+    #[inline]
+    #[must_use]
+    pub fn num_threads(&self) -> usize {
+        let threads = self.threads.unwrap_or_else(num_cpus::get);
+        debug_assert!(threads > 0);
+
+        threads
+    }
+
+    /// List the settings as named text values, for recording alongside output data.
+    #[inline]
+    #[must_use]
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        let none = || "none".to_string();
+
+        vec![
+            ("block_size", self.block_size.to_string()),
+            ("num_phot", self.num_phot.to_string()),
+            ("seed", self.seed.to_string()),
+            ("threads", self.num_threads().to_string()),
+            (
+                "checkpoint",
+                self.checkpoint.map_or_else(none, |c| c.to_string()),
+            ),
+            ("batches", self.batches.map_or_else(none, |b| b.to_string())),
+            ("bump_dist", self.bump_dist.to_string()),
+            ("loop_limit", self.loop_limit.to_string()),
+            ("roulette_weight", self.roulette_weight.to_string()),
+            ("roulette_barrels", self.roulette_barrels.to_string()),
+            ("init_mat", self.init_mat.clone()),
+            ("range", self.range.to_string()),
+            (
+                "periodic",
+                format!(
+                    "[{}, {}, {}]",
+                    self.periodic[X], self.periodic[Y], self.periodic[Z]
+                ),
+            ),
+            (
+                "time_bins",
+                self.time_bins.as_ref().map_or_else(none, |binner| {
+                    format!("{} ({} bins)", binner.range(), binner.bins())
+                }),
+            ),
+            (
+                "polariser",
+                self.polariser.as_ref().map_or_else(none, |polariser| {
+                    let stokes = polariser.stokes();
+                    let axis = polariser.axis();
+                    format!(
+                        "stokes [{}, {}, {}, {}], axis [{}, {}, {}]",
+                        stokes.i(),
+                        stokes.q(),
+                        stokes.u(),
+                        stokes.v(),
+                        axis.x,
+                        axis.y,
+                        axis.z
+                    )
+                }),
+            ),
+            ("tag_sources", self.tag_sources().to_string()),
+            (
+                "paths",
+                self.paths.as_ref().map_or_else(none, |paths| match paths {
+                    Recording::First(n) => format!("first {}", n),
+                    Recording::Sample(p) => format!("sample {}", p),
+                }),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
 }
 
 impl Display for Settings {
@@ -42,11 +159,47 @@ impl Display for Settings {
     fn fmt(&self, fmt: &mut Formatter) -> Result {
         display_field_ln!(fmt, "block size", self.block_size)?;
         display_field_ln!(fmt, "number of photons", self.num_phot)?;
+        display_field_ln!(fmt, "seed", self.seed)?;
+        display_field_ln!(fmt, "number of threads", self.num_threads())?;
+        if let Some(checkpoint) = self.checkpoint {
+            display_field_ln!(fmt, "checkpoint interval", checkpoint, "photons")?;
+        } else {
+            display_field_ln!(fmt, "checkpoint interval", "none")?;
+        }
+        if let Some(batches) = self.batches {
+            display_field_ln!(fmt, "uncertainty batches", batches)?;
+        } else {
+            display_field_ln!(fmt, "uncertainty batches", "none")?;
+        }
         display_field_ln!(fmt, "bump distance", self.bump_dist, "m")?;
         display_field_ln!(fmt, "loop limit", self.loop_limit)?;
         display_field_ln!(fmt, "roulette weight", self.roulette_weight)?;
         display_field_ln!(fmt, "roulette barrels", self.roulette_barrels)?;
         display_field_ln!(fmt, "initial material", &self.init_mat)?;
-        display_field!(fmt, "wavelength range", &self.range, "m")
+        display_field_ln!(fmt, "wavelength range", &self.range, "m")?;
+        display_field_ln!(
+            fmt,
+            "periodic",
+            format!(
+                "[{}, {}, {}]",
+                self.periodic[X], self.periodic[Y], self.periodic[Z]
+            )
+        )?;
+        if let Some(time_bins) = &self.time_bins {
+            display_field_ln!(fmt, "time bins", time_bins, "s")?;
+        } else {
+            display_field_ln!(fmt, "time bins", "none")?;
+        }
+        if let Some(polariser) = &self.polariser {
+            display_field_ln!(fmt, "polariser", polariser)?;
+        } else {
+            display_field_ln!(fmt, "polariser", "none")?;
+        }
+        display_field_ln!(fmt, "tag sources", self.tag_sources())?;
+        if let Some(paths) = &self.paths {
+            display_field!(fmt, "paths", paths)
+        } else {
+            display_field!(fmt, "paths", "none")
+        }
     }
 }
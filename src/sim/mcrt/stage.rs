@@ -0,0 +1,272 @@
+//! Simulation stage implementation.
+
+use crate::{
+    display_field, display_field_ln,
+    grid::Grid,
+    mcrt::{life::Life, run, Detector, Input, Interface, Light, Material, Output, Settings},
+    tree::Cell,
+    Error, Group, Mesh, Pos3, Probability, Set,
+};
+use attr::load;
+use ndarray::Array3;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result},
+    path::Path,
+};
+
+/// Output tally from which a derived source is weighted.
+#[load]
+#[derive(Clone, Copy)]
+pub enum Tally {
+    /// Local absorptions.
+    Absorption,
+    /// Local shifts.
+    Shift,
+    /// Local unshifted light energy.
+    Energy,
+}
+
+impl Tally {
+    /// Reference the corresponding tally of the output data.
+    #[inline]
+    #[must_use]
+    pub fn select(self, data: &Output) -> &Array3<f64> {
+        match self {
+            Self::Absorption => &data.absorptions,
+            Self::Shift => &data.shifts,
+            Self::Energy => &data.energy,
+        }
+    }
+}
+
+impl Display for Tally {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let kind = match self {
+            Self::Absorption => "Absorption",
+            Self::Shift => "Shift",
+            Self::Energy => "Energy",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
+
+/// Placement of a derived source.
+#[load]
+pub enum Sampling {
+    /// Emit from within every voxel, weighted by its tally.
+    Field,
+    /// Emit from the listed points, each weighted by the tally of its voxel.
+    Points(Vec<Pos3>),
+}
+
+impl Display for Sampling {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Field => write!(fmt, "Voxel field"),
+            Self::Points(points) => write!(fmt, "{} points", points.len()),
+        }
+    }
+}
+
+/// Stage emission source enumeration.
+pub enum Source {
+    /// Fixed light sources.
+    Lights(Set<Light>),
+    /// Source derived from the output of a previous stage.
+    Derived {
+        /// Name of the previous stage.
+        from: Group,
+        /// Tally weighting the emission.
+        tally: Tally,
+        /// Emission placement.
+        sampling: Sampling,
+        /// Emission spectrum.
+        spec: Probability,
+        /// Power [J/s].
+        power: f64,
+    },
+}
+
+impl Source {
+    /// Derive a light from the output data of the previous stages.
+    /// Fixed sources are not derived.
+    /// # Errors
+    /// if the source stage has not yet been run,
+    /// a point lies outside the grid,
+    /// or the source tally is zero throughout.
+    #[inline]
+    pub fn derive(
+        &self,
+        grid: &Grid,
+        outputs: &BTreeMap<Group, Output>,
+    ) -> std::result::Result<Option<Light>, Error> {
+        let (from, tally, sampling, spec, power) = match self {
+            Self::Lights(..) => return Ok(None),
+            Self::Derived {
+                from,
+                tally,
+                sampling,
+                spec,
+                power,
+            } => (from, tally, sampling, spec, power),
+        };
+
+        let data = outputs.get(from).ok_or_else(|| {
+            Error::Text(format!(
+                "Stage source {} must be run before it can be derived from.",
+                from
+            ))
+        })?;
+        let weights = tally.select(data);
+
+        let light = match sampling {
+            Sampling::Field => {
+                let voxels: Vec<_> = weights
+                    .indexed_iter()
+                    .map(|((xi, yi, zi), w)| (grid.voxel([xi, yi, zi]), *w))
+                    .collect();
+                if voxels.iter().all(|(_v, w)| *w <= 0.0) {
+                    return Err(Error::Text(format!(
+                        "Stage {} {} tally is zero throughout.",
+                        from, tally
+                    )));
+                }
+                Light::new_field(voxels, spec.clone(), *power)
+            }
+            Sampling::Points(points) => {
+                let mut weighted = Vec::with_capacity(points.len());
+                for p in points {
+                    let index = grid.gen_index(p).ok_or_else(|| {
+                        Error::Text(format!("Derived source point {} lies outside the grid.", p))
+                    })?;
+                    weighted.push((*p, weights[index]));
+                }
+                if weighted.iter().all(|(_p, w)| *w <= 0.0) {
+                    return Err(Error::Text(format!(
+                        "Stage {} {} tally is zero at every point.",
+                        from, tally
+                    )));
+                }
+                Light::new_points(weighted, spec.clone(), *power)
+            }
+        };
+
+        Ok(Some(light))
+    }
+}
+
+impl Display for Source {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::Lights(lights) => display_field!(fmt, "lights", lights),
+            Self::Derived {
+                from,
+                tally,
+                sampling,
+                spec,
+                power,
+            } => {
+                display_field_ln!(fmt, "derived from", from)?;
+                display_field_ln!(fmt, "tally", tally)?;
+                display_field_ln!(fmt, "sampling", sampling)?;
+                display_field_ln!(fmt, "spectrum", spec)?;
+                display_field!(fmt, "power", power, "J/s")
+            }
+        }
+    }
+}
+
+/// Simulation stage structure.
+pub struct Stage {
+    /// Stage name.
+    name: Group,
+    /// Emission source.
+    source: Source,
+}
+
+impl Stage {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub const fn new(name: Group, source: Source) -> Self {
+        Self { name, source }
+    }
+
+    /// Reference the stage name.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &Group {
+        &self.name
+    }
+
+    /// Reference the emission source.
+    #[inline]
+    #[must_use]
+    pub const fn source(&self) -> &Source {
+        &self.source
+    }
+}
+
+impl Display for Stage {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "name", &self.name)?;
+        display_field!(fmt, "source", &self.source)
+    }
+}
+
+/// Run a stage, given the outputs of the stages preceding it, each sharing the same settings and geometry.
+/// The stage checkpoints within, and resumes from, a sub-directory named after it.
+/// # Errors
+/// if the stage name repeats that of a preceding stage,
+/// a derived source can not be constructed,
+/// or the simulation fails.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn run_stage(
+    stage: &Stage,
+    outputs: &BTreeMap<Group, Output>,
+    sett: &Settings,
+    mats: &Set<Material>,
+    inters: &Set<Interface>,
+    dets: &Set<Detector>,
+    surfs: &Set<Mesh>,
+    tree: &Cell,
+    grid: &Grid,
+    func: Life,
+    check_dir: &Path,
+    resume_dir: Option<&Path>,
+) -> std::result::Result<Output, Error> {
+    if outputs.contains_key(stage.name()) {
+        return Err(Error::Text(format!(
+            "Duplicate stage name: {}",
+            stage.name()
+        )));
+    }
+
+    let derived = stage
+        .source()
+        .derive(grid, outputs)?
+        .map(|light| Set::from_vec(vec![(stage.name().clone(), light)]));
+    let lights = match (stage.source(), &derived) {
+        (Source::Lights(lights), _) => lights,
+        (Source::Derived { .. }, Some(derived)) => derived,
+        (Source::Derived { .. }, None) => unreachable!(),
+    };
+
+    let input = Input::new(sett, lights, mats, inters, dets, surfs, tree, grid);
+    run::simulate(
+        &input,
+        func,
+        &check_dir.join(stage.name()),
+        resume_dir.map(|dir| dir.join(stage.name())).as_deref(),
+    )
+}
@@ -0,0 +1,167 @@
+//! Trajectory implementation.
+
+use crate::{display_field, Error, Pos3};
+use attr::load;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter, Result},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Path recording selection enumeration.
+#[load]
+pub enum Recording {
+    /// Record the first given number of photons.
+    First(u64),
+    /// Record each photon with the given probability.
+    Sample(f64),
+}
+
+impl Display for Recording {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match self {
+            Self::First(n) => display_field!(fmt, "first photons", n),
+            Self::Sample(p) => display_field!(fmt, "sample probability", p),
+        }
+    }
+}
+
+/// Trajectory vertex event enumeration.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub enum Step {
+    /// Emission from the light source.
+    Emission,
+    /// Scattering or re-emission event.
+    Scatter,
+    /// Reflection from a surface.
+    Reflection,
+    /// Refraction through a surface.
+    Refraction,
+    /// Re-entry through a periodic boundary.
+    Wrap,
+    /// Exit from the grid, or termination.
+    Exit,
+}
+
+impl Step {
+    /// Get the integer code of the event, as written to VTK files.
+    #[inline]
+    #[must_use]
+    pub const fn code(self) -> i32 {
+        match self {
+            Self::Emission => 0,
+            Self::Scatter => 1,
+            Self::Reflection => 2,
+            Self::Refraction => 3,
+            Self::Wrap => 4,
+            Self::Exit => 5,
+        }
+    }
+}
+
+/// Recorded photon polyline.
+#[derive(Deserialize, Serialize)]
+pub struct Trajectory {
+    /// Vertices and their events.
+    verts: Vec<(Pos3, Step)>,
+}
+
+impl Trajectory {
+    /// Construct a new instance starting at the given vertex.
+    #[inline]
+    #[must_use]
+    pub fn new(pos: Pos3, step: Step) -> Self {
+        Self {
+            verts: vec![(pos, step)],
+        }
+    }
+
+    /// Reference the vertices.
+    #[inline]
+    #[must_use]
+    pub fn verts(&self) -> &[(Pos3, Step)] {
+        &self.verts
+    }
+
+    /// Add a vertex.
+    #[inline]
+    pub fn push(&mut self, pos: Pos3, step: Step) {
+        self.verts.push((pos, step));
+    }
+}
+
+/// Save a list of trajectories as Wavefront OBJ line elements.
+/// # Errors
+/// if the file can not be written.
+#[inline]
+pub fn save_obj(trajs: &[Trajectory], path: &Path) -> std::result::Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    for traj in trajs {
+        for (pos, _step) in traj.verts() {
+            writeln!(file, "v {} {} {}", pos.x, pos.y, pos.z)?;
+        }
+    }
+
+    let mut offset = 1;
+    for traj in trajs {
+        write!(file, "l")?;
+        for index in offset..(offset + traj.verts().len()) {
+            write!(file, " {}", index)?;
+        }
+        writeln!(file)?;
+        offset += traj.verts().len();
+    }
+
+    file.flush()?;
+    Ok(())
+}
+
+/// Save a list of trajectories as legacy VTK polydata, with the event code of each vertex as point data.
+/// # Errors
+/// if the file can not be written.
+#[inline]
+pub fn save_vtk(trajs: &[Trajectory], path: &Path) -> std::result::Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    let num_verts: usize = trajs.iter().map(|traj| traj.verts().len()).sum();
+
+    writeln!(file, "# vtk DataFile Version 3.0")?;
+    writeln!(file, "Photon trajectories")?;
+    writeln!(file, "ASCII")?;
+    writeln!(file, "DATASET POLYDATA")?;
+
+    writeln!(file, "POINTS {} double", num_verts)?;
+    for traj in trajs {
+        for (pos, _step) in traj.verts() {
+            writeln!(file, "{} {} {}", pos.x, pos.y, pos.z)?;
+        }
+    }
+
+    writeln!(file, "LINES {} {}", trajs.len(), num_verts + trajs.len())?;
+    let mut offset = 0;
+    for traj in trajs {
+        write!(file, "{}", traj.verts().len())?;
+        for index in offset..(offset + traj.verts().len()) {
+            write!(file, " {}", index)?;
+        }
+        writeln!(file)?;
+        offset += traj.verts().len();
+    }
+
+    writeln!(file, "POINT_DATA {}", num_verts)?;
+    writeln!(file, "SCALARS step int 1")?;
+    writeln!(file, "LOOKUP_TABLE default")?;
+    for traj in trajs {
+        for (_pos, step) in traj.verts() {
+            writeln!(file, "{}", step.code())?;
+        }
+    }
+
+    file.flush()?;
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Uncertainty implementation.
+
+use crate::{clone, Error};
+use ndarray::{Array3, Zip};
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// Batch-means accumulation of a single voxel tally.
+#[derive(Deserialize, Serialize)]
+pub struct Moment {
+    /// Sum of squared batch tallies.
+    sq: Array3<f64>,
+    /// Tally at the start of the current batch.
+    start: Array3<f64>,
+}
+
+impl Moment {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(res: [usize; 3]) -> Self {
+        Self {
+            sq: Array3::zeros(res),
+            start: Array3::zeros(res),
+        }
+    }
+
+    /// Close the current batch, given the running tally, and open the next.
+    #[inline]
+    pub fn end_batch(&mut self, tally: &Array3<f64>) {
+        Zip::from(&mut self.sq)
+            .and(&mut self.start)
+            .and(tally)
+            .apply(|sq, start, total| {
+                let batch = total - *start;
+                *sq += batch * batch;
+                *start = *total;
+            });
+    }
+
+    /// Calculate the relative error of each voxel of the total tally over a number of equally sized batches.
+    /// Voxels which were never scored, or runs with fewer than two batches, have undefined error.
+    #[inline]
+    #[must_use]
+    pub fn rel_err(&self, tally: &Array3<f64>, num_batches: u64) -> Array3<f64> {
+        let n = num_batches as f64;
+
+        let mut rel_err = Array3::from_elem(tally.raw_dim(), std::f64::NAN);
+        if num_batches < 2 {
+            return rel_err;
+        }
+
+        Zip::from(&mut rel_err)
+            .and(&self.sq)
+            .and(tally)
+            .apply(|err, sq, total| {
+                if *total > 0.0 {
+                    *err = ((n * sq / (total * total) - 1.0).max(0.0) / (n - 1.0)).sqrt();
+                }
+            });
+
+        rel_err
+    }
+}
+
+impl AddAssign<&Self> for Moment {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.sq += &rhs.sq;
+    }
+}
+
+/// Batch-means uncertainty estimation of the voxel energy tallies.
+#[derive(Deserialize, Serialize)]
+pub struct Uncertainty {
+    /// Number of completed batches.
+    num_batches: u64,
+    /// Unshifted light energy moments.
+    pub energy: Moment,
+    /// Absorption moments.
+    pub absorptions: Moment,
+    /// Shift moments.
+    pub shifts: Moment,
+    /// Shifted light energy moments.
+    pub shifted_energy: Moment,
+}
+
+impl Uncertainty {
+    clone!(num_batches, u64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(res: [usize; 3]) -> Self {
+        Self {
+            num_batches: 0,
+            energy: Moment::new(res),
+            absorptions: Moment::new(res),
+            shifts: Moment::new(res),
+            shifted_energy: Moment::new(res),
+        }
+    }
+
+    /// Close the current batch of each tally.
+    #[inline]
+    pub fn end_batch(
+        &mut self,
+        energy: &Array3<f64>,
+        absorptions: &Array3<f64>,
+        shifts: &Array3<f64>,
+        shifted_energy: &Array3<f64>,
+    ) {
+        self.energy.end_batch(energy);
+        self.absorptions.end_batch(absorptions);
+        self.shifts.end_batch(shifts);
+        self.shifted_energy.end_batch(shifted_energy);
+
+        self.num_batches += 1;
+    }
+}
+
+impl AddAssign<&Self> for Uncertainty {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.num_batches += rhs.num_batches;
+        self.energy += &rhs.energy;
+        self.absorptions += &rhs.absorptions;
+        self.shifts += &rhs.shifts;
+        self.shifted_energy += &rhs.shifted_energy;
+    }
+}
+
+/// Determine the fraction of voxels with a relative error below the given threshold.
+#[inline]
+#[must_use]
+pub fn fraction_below(rel_err: &Array3<f64>, threshold: f64) -> f64 {
+    let count = rel_err.iter().filter(|err| **err < threshold).count();
+
+    count as f64 / rel_err.len() as f64
+}
+
+/// Check that photons divide evenly between an explicit number of threads, and each thread's photons between uncertainty batches,
+/// so that every batch holds the same number of photons on any machine.
+/// # Errors
+/// if the number of threads is not given,
+/// the number of batches is zero,
+/// or the number of photons is not a multiple of the number of threads and batches.
+#[inline]
+pub fn check_batches(num_phot: u64, threads: Option<usize>, batches: u64) -> Result<(), Error> {
+    let num_threads = threads.ok_or_else(|| {
+        Error::Text(
+            "Uncertainty batches require an explicit number of threads, so that batches do not depend upon the machine."
+                .to_string(),
+        )
+    })?;
+    if batches == 0 {
+        return Err(Error::Text(
+            "Number of uncertainty batches must be positive.".to_string(),
+        ));
+    }
+
+    let total_batches = num_threads as u64 * batches;
+    if num_phot % total_batches != 0 {
+        return Err(Error::Text(format!(
+            "Number of photons {} must divide evenly between {} threads of {} uncertainty batches.",
+            num_phot, num_threads, batches
+        )));
+    }
+
+    Ok(())
+}
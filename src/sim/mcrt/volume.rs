@@ -0,0 +1,131 @@
+//! Voxel property volume implementation.
+
+use crate::{display_field, display_field_ln, grid::Grid, mcrt::Material, Error, Set};
+use ndarray::Array3;
+use std::fmt::{Display, Formatter, Result};
+
+/// Voxel-resolved optical properties, aligned with the regular grid.
+/// Each given array replaces the corresponding material formula within each voxel.
+pub struct Volume {
+    /// Optional refractive indices.
+    ref_index: Option<Array3<f64>>,
+    /// Optional scattering coefficients [1/m].
+    scat_coeff: Option<Array3<f64>>,
+    /// Optional absorption coefficients [1/m].
+    abs_coeff: Option<Array3<f64>>,
+    /// Optional asymmetry factors.
+    asym_fact: Option<Array3<f64>>,
+}
+
+impl Volume {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        ref_index: Option<Array3<f64>>,
+        scat_coeff: Option<Array3<f64>>,
+        abs_coeff: Option<Array3<f64>>,
+        asym_fact: Option<Array3<f64>>,
+    ) -> Self {
+        debug_assert!(
+            ref_index.is_some()
+                || scat_coeff.is_some()
+                || abs_coeff.is_some()
+                || asym_fact.is_some()
+        );
+
+        Self {
+            ref_index,
+            scat_coeff,
+            abs_coeff,
+            asym_fact,
+        }
+    }
+
+    /// Iterate over the given arrays.
+    #[inline]
+    fn arrays(&self) -> impl Iterator<Item = &Array3<f64>> {
+        self.ref_index
+            .iter()
+            .chain(self.scat_coeff.iter())
+            .chain(self.abs_coeff.iter())
+            .chain(self.asym_fact.iter())
+    }
+
+    /// Determine the resolution of the volume.
+    #[inline]
+    #[must_use]
+    pub fn res(&self) -> [usize; 3] {
+        let shape = self
+            .arrays()
+            .next()
+            .expect("Empty property volume.")
+            .shape();
+
+        [shape[0], shape[1], shape[2]]
+    }
+
+    /// Look up the refractive index, scattering, absorption and asymmetry values of a voxel,
+    /// falling back to the given values where no array is set.
+    #[inline]
+    #[must_use]
+    pub fn voxel(
+        &self,
+        index: [usize; 3],
+        (ref_index, scat_coeff, abs_coeff, asym_fact): (f64, f64, f64, f64),
+    ) -> (f64, f64, f64, f64) {
+        (
+            self.ref_index.as_ref().map_or(ref_index, |arr| arr[index]),
+            self.scat_coeff
+                .as_ref()
+                .map_or(scat_coeff, |arr| arr[index]),
+            self.abs_coeff.as_ref().map_or(abs_coeff, |arr| arr[index]),
+            self.asym_fact.as_ref().map_or(asym_fact, |arr| arr[index]),
+        )
+    }
+}
+
+/// Check that the volume of each material matches the resolution of the grid.
+/// # Errors
+/// if any volume array differs in resolution from the grid.
+#[inline]
+pub fn check_volumes(mats: &Set<Material>, grid: &Grid) -> std::result::Result<(), Error> {
+    for (group, mat) in mats.map() {
+        if let Some(vol) = mat.volume() {
+            if vol.arrays().any(|arr| arr.shape() != grid.res()) {
+                return Err(Error::Text(format!(
+                    "Material {} property volume does not match the grid resolution {:?}.",
+                    group,
+                    grid.res()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for Volume {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        let res = self.res();
+        display_field_ln!(
+            fmt,
+            "resolution",
+            format!("[{}, {}, {}]", res[0], res[1], res[2])
+        )?;
+        display_field_ln!(fmt, "voxel refractive index", self.ref_index.is_some())?;
+        display_field_ln!(
+            fmt,
+            "voxel scattering coefficient",
+            self.scat_coeff.is_some()
+        )?;
+        display_field_ln!(
+            fmt,
+            "voxel absorption coefficient",
+            self.abs_coeff.is_some()
+        )?;
+        display_field!(fmt, "voxel asymmetry factor", self.asym_fact.is_some())
+    }
+}
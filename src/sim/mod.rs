@@ -1,4 +1,7 @@
 //! Simulation module.
 
+pub mod diffuse;
+pub mod heat;
 pub mod mcrt;
+pub mod pdt;
 // pub mod render;
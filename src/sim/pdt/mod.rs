@@ -0,0 +1,8 @@
+//! Photodynamic therapy module.
+
+pub mod output;
+pub mod photosensitiser;
+pub mod run;
+pub mod settings;
+
+pub use self::{output::*, photosensitiser::*, run::*, settings::*};
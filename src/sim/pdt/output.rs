@@ -0,0 +1,88 @@
+//! Output data structure.
+
+use crate::{display_field, display_field_ln, Error, Save};
+use ndarray::Array3;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// Photodynamic therapy output data structure.
+pub struct Output {
+    /// Cell volume [m^3].
+    cell_vol: f64,
+    /// Cumulative singlet oxygen dose [mol/m^3].
+    pub dose: Array3<f64>,
+    /// Remaining ground state photosensitiser concentration [mol/m^3].
+    pub photosensitiser: Array3<f64>,
+    /// Time [s] at which the necrosis threshold is reached, or NaN if it is not.
+    pub threshold_time: Array3<f64>,
+}
+
+impl Output {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(res: [usize; 3], cell_vol: f64) -> Self {
+        debug_assert!(cell_vol > 0.0);
+
+        Self {
+            cell_vol,
+            dose: Array3::zeros(res),
+            photosensitiser: Array3::zeros(res),
+            threshold_time: Array3::from_elem(res, std::f64::NAN),
+        }
+    }
+
+    /// Create the necrosis map, unity where the threshold was reached and zero elsewhere.
+    #[inline]
+    #[must_use]
+    pub fn necrosis(&self) -> Array3<f64> {
+        self.threshold_time
+            .map(|t| if t.is_nan() { 0.0 } else { 1.0 })
+    }
+
+    /// Calculate the total necrotic volume [m^3].
+    #[inline]
+    #[must_use]
+    pub fn necrotic_volume(&self) -> f64 {
+        self.threshold_time.iter().filter(|t| !t.is_nan()).count() as f64 * self.cell_vol
+    }
+}
+
+impl Save for Output {
+    #[inline]
+    fn save(&self, out_dir: &Path) -> Result<(), Error> {
+        let path = out_dir.join("dose.nc");
+        println!("saving: {}", path.display());
+        self.dose.save(&path)?;
+
+        let path = out_dir.join("photosensitiser.nc");
+        println!("saving: {}", path.display());
+        self.photosensitiser.save(&path)?;
+
+        let path = out_dir.join("threshold_time.nc");
+        println!("saving: {}", path.display());
+        self.threshold_time.save(&path)?;
+
+        let path = out_dir.join("necrosis.nc");
+        println!("saving: {}", path.display());
+        self.necrosis().save(&path)?;
+
+        Ok(())
+    }
+}
+
+impl Display for Output {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        display_field_ln!(
+            fmt,
+            "max dose",
+            self.dose.iter().fold(0.0, |max: f64, d| max.max(*d)),
+            "mol/m^3"
+        )?;
+        display_field!(fmt, "necrotic volume", self.necrotic_volume(), "m^3")
+    }
+}
@@ -0,0 +1,70 @@
+//! Photosensitiser implementation.
+
+use crate::{clone, display_field, display_field_ln, Formula};
+use physical_constants::{AVOGADRO_CONSTANT, PLANCK_CONSTANT, SPEED_OF_LIGHT_IN_VACUUM};
+use std::{
+    f64::consts::LN_10,
+    fmt::{Display, Formatter, Result},
+};
+
+/// Photosensitiser structure.
+/// Ground state photosensitiser, of concentration S, absorbs light and generates singlet oxygen,
+/// which in turn photobleaches the photosensitiser:
+/// dD/dt = yield * k * S,
+/// dS/dt = -bleaching * S * dD/dt,
+/// where D is the cumulative singlet oxygen dose, and k the molar absorption rate.
+pub struct Photosensitiser {
+    /// Decadic molar extinction coefficient [m^2/mol] as a function of wavelength [m].
+    extinction: Formula,
+    /// Singlet oxygen quantum yield.
+    singlet_yield: f64,
+    /// Singlet oxygen mediated photobleaching coefficient [m^3/mol].
+    bleaching: f64,
+}
+
+impl Photosensitiser {
+    clone!(singlet_yield, f64);
+    clone!(bleaching, f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(extinction: Formula, singlet_yield: f64, bleaching: f64) -> Self {
+        debug_assert!(singlet_yield >= 0.0);
+        debug_assert!(singlet_yield <= 1.0);
+        debug_assert!(bleaching >= 0.0);
+
+        Self {
+            extinction,
+            singlet_yield,
+            bleaching,
+        }
+    }
+
+    /// Determine the decadic molar extinction coefficient [m^2/mol] at the given wavelength [m].
+    #[inline]
+    #[must_use]
+    pub fn extinction(&self, wavelength: f64) -> f64 {
+        self.extinction.y(wavelength)
+    }
+
+    /// Calculate the rate [1/s] at which each mole of ground state photosensitiser absorbs photons,
+    /// under a fluence rate [W/m^2] of the given wavelength [m].
+    #[inline]
+    #[must_use]
+    pub fn absorption_rate(&self, wavelength: f64, fluence_rate: f64) -> f64 {
+        let photon_flux = fluence_rate * wavelength
+            / (PLANCK_CONSTANT * SPEED_OF_LIGHT_IN_VACUUM * AVOGADRO_CONSTANT);
+
+        LN_10 * self.extinction(wavelength) * photon_flux
+    }
+}
+
+impl Display for Photosensitiser {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "singlet oxygen yield", self.singlet_yield)?;
+        display_field!(fmt, "photobleaching", self.bleaching, "m^3/mol")
+    }
+}
@@ -0,0 +1,136 @@
+//! Photodynamic therapy dose calculation functions.
+
+use crate::{
+    mcrt::{check_materials, Interface},
+    ode::Event,
+    pdt::{Output, Photosensitiser, Settings},
+    Error, Group, Set,
+};
+use ndarray::{array, Array3};
+use rayon::prelude::*;
+
+/// State vector index of the ground state photosensitiser concentration.
+const PHOTOSENSITISER: usize = 0;
+/// State vector index of the cumulative singlet oxygen dose.
+const DOSE: usize = 1;
+
+/// Check that every uptake concentration is non-negative, that every surface group has an interface,
+/// and that the initial material and every interface refers to a material with an uptake concentration.
+/// # Errors
+/// if a concentration is negative,
+/// a surface group has no interface,
+/// or a material has no uptake concentration.
+#[inline]
+pub fn check_uptake<T>(
+    surfs: &Set<T>,
+    inters: &Set<Interface>,
+    uptake: &Set<f64>,
+    init_mat: &str,
+) -> Result<(), Error> {
+    if let Some((group, _conc)) = uptake.map().iter().find(|(_group, conc)| **conc < 0.0) {
+        return Err(Error::Text(format!(
+            "Material {} uptake concentration must be non-negative.",
+            group
+        )));
+    }
+
+    check_materials(surfs, inters, uptake, init_mat)
+}
+
+/// Map the initial photosensitiser concentration [mol/m^3] of each voxel from the uptake of its material.
+/// # Errors
+/// if a voxel material has no uptake concentration.
+#[inline]
+pub fn uptake_map(uptake: &Set<f64>, groups: &Array3<&Group>) -> Result<Array3<f64>, Error> {
+    let mut concs = Vec::with_capacity(groups.len());
+    for group in groups.iter() {
+        concs.push(*uptake.map().get(*group).ok_or_else(|| {
+            Error::Text(format!("Material {} has no photosensitiser uptake.", group))
+        })?);
+    }
+
+    Ok(Array3::from_shape_vec(groups.raw_dim(), concs)?)
+}
+
+/// Integrate the photosensitiser kinetics of each voxel over the treatment time.
+/// # Errors
+/// if the fluence rate and concentration maps differ in shape,
+/// or the kinetics of a voxel can not be integrated.
+#[inline]
+pub fn simulate(
+    sett: &Settings,
+    ps: &Photosensitiser,
+    fluence_rate: &Array3<f64>,
+    concs: &Array3<f64>,
+    cell_vol: f64,
+) -> Result<Output, Error> {
+    if fluence_rate.shape() != concs.shape() {
+        return Err(Error::Text(
+            "Fluence rate and photosensitiser maps must have the same resolution.".to_string(),
+        ));
+    }
+
+    let results: Vec<_> = fluence_rate
+        .as_slice()
+        .ok_or("Missing fluence rate slice data.")?
+        .par_iter()
+        .zip(
+            concs
+                .as_slice()
+                .ok_or("Missing concentration slice data.")?
+                .par_iter(),
+        )
+        .map(|(fluence_rate, conc)| {
+            voxel(
+                sett,
+                ps,
+                ps.absorption_rate(sett.wavelength(), *fluence_rate),
+                *conc,
+            )
+        })
+        .collect();
+
+    let results = results.into_iter().collect::<Result<Vec<_>, Error>>()?;
+    let shape = concs.shape();
+    let mut data = Output::new([shape[0], shape[1], shape[2]], cell_vol);
+    data.photosensitiser =
+        Array3::from_shape_vec(concs.raw_dim(), results.iter().map(|r| r.0).collect())?;
+    data.dose = Array3::from_shape_vec(concs.raw_dim(), results.iter().map(|r| r.1).collect())?;
+    data.threshold_time =
+        Array3::from_shape_vec(concs.raw_dim(), results.iter().map(|r| r.2).collect())?;
+
+    Ok(data)
+}
+
+/// Integrate the kinetics of a single voxel, given its molar absorption rate [1/s] and initial concentration [mol/m^3].
+/// Returns the final photosensitiser concentration, the dose, and the time at which the threshold was reached.
+/// # Errors
+/// if the kinetics can not be integrated.
+#[inline]
+fn voxel(
+    sett: &Settings,
+    ps: &Photosensitiser,
+    abs_rate: f64,
+    conc: f64,
+) -> Result<(f64, f64, f64), Error> {
+    // Voxels without photosensitiser or light receive no dose.
+    if conc <= 0.0 || abs_rate <= 0.0 {
+        return Ok((conc, 0.0, std::f64::NAN));
+    }
+
+    let singlet_rate = ps.singlet_yield() * abs_rate;
+    let bleaching = ps.bleaching();
+    let rates = |_t: f64, y: &ndarray::Array1<f64>| {
+        let dose_rate = singlet_rate * y[PHOTOSENSITISER];
+        array![-bleaching * y[PHOTOSENSITISER] * dose_rate, dose_rate]
+    };
+
+    let events = [Event::new_threshold(DOSE, sett.threshold(), false)];
+    let sol = sett
+        .integrator()
+        .integrate(rates, 0.0, array![conc, 0.0], sett.time(), &events)?;
+
+    let threshold_time = sol.occurrences().first().map_or(std::f64::NAN, |occ| occ.t);
+
+    Ok((sol.y()[PHOTOSENSITISER], sol.y()[DOSE], threshold_time))
+}
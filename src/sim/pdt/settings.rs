@@ -0,0 +1,44 @@
+//! Settings implementation.
+
+use crate::{access, clone, display_field, display_field_ln, ode::Integrator, Group};
+use attr::load;
+use std::fmt::{Display, Formatter, Result};
+
+/// Photodynamic therapy settings structure.
+#[load]
+pub struct Settings {
+    /// Treatment time [s].
+    time: f64,
+    /// Treatment light wavelength [m].
+    wavelength: f64,
+    /// Singlet oxygen dose [mol/m^3] above which tissue is necrotic.
+    threshold: f64,
+    /// Kinetic model integrator.
+    integrator: Integrator,
+    /// Bump distance [m].
+    bump_dist: f64,
+    /// Initial material.
+    init_mat: Group,
+}
+
+impl Settings {
+    clone!(time, f64);
+    clone!(wavelength, f64);
+    clone!(threshold, f64);
+    access!(integrator, Integrator);
+    clone!(bump_dist, f64);
+    access!(init_mat, Group);
+}
+
+impl Display for Settings {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "treatment time", self.time, "s")?;
+        display_field_ln!(fmt, "wavelength", self.wavelength, "m")?;
+        display_field_ln!(fmt, "necrosis threshold", self.threshold, "mol/m^3")?;
+        display_field_ln!(fmt, "integrator", &self.integrator)?;
+        display_field_ln!(fmt, "bump distance", self.bump_dist, "m")?;
+        display_field!(fmt, "initial material", &self.init_mat)
+    }
+}
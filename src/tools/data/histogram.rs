@@ -2,9 +2,11 @@
 
 use crate::{access, Binner, Error, Range, Save};
 use ndarray::Array1;
+use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Write, ops::AddAssign, path::Path};
 
 /// Static range, constant bin width, Histogram.
+#[derive(Deserialize, Serialize)]
 pub struct Histogram {
     /// Binner.
     binner: Binner,
@@ -29,6 +31,18 @@ impl Histogram {
         }
     }
 
+    /// Construct a new instance from a binner.
+    #[inline]
+    #[must_use]
+    pub fn new_binner(binner: Binner) -> Self {
+        let bins = binner.bins() as usize;
+
+        Self {
+            binner,
+            counts: Array1::zeros(bins),
+        }
+    }
+
     /// Increment the bin corresponding to x by unity.
     #[inline]
     pub fn collect(&mut self, x: f64) {
@@ -1,9 +1,13 @@
 //! Binner implementation.
 
-use crate::{clone, Range};
+use crate::{clone, display_field, display_field_ln, Range};
+use attr::load;
+use serde::Serialize;
+use std::fmt::{Display, Formatter, Result};
 
 /// One-dimensional binning structure.
-#[derive(Debug, Clone, PartialEq)]
+#[load]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Binner {
     /// Range.
     range: Range,
@@ -53,3 +57,12 @@ impl Binner {
         }
     }
 }
+
+impl Display for Binner {
+    #[allow(clippy::result_expect_used)]
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        display_field_ln!(fmt, "range", &self.range)?;
+        display_field!(fmt, "bins", self.bins)
+    }
+}
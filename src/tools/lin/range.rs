@@ -2,6 +2,7 @@
 
 use crate::clone;
 use attr::load;
+use serde::Serialize;
 use std::{
     f64::{INFINITY, NEG_INFINITY},
     fmt::{Display, Formatter, Result},
@@ -9,7 +10,7 @@ use std::{
 
 /// One-dimensional inclusive Range.
 #[load]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Range {
     /// Minimum bound.
     min: f64,
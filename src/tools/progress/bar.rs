@@ -57,6 +57,13 @@ impl Bar {
         }
     }
 
+    /// Increment the progress by a given amount.
+    #[inline]
+    pub fn inc(&mut self, delta: u64) {
+        self.count += delta;
+        self.pb.inc(delta);
+    }
+
     /// Check if the progress bar is complete.
     #[inline]
     #[must_use]
@@ -17,3 +17,12 @@ macro_rules! args {
         }
     };
 }
+
+/// Find the value following a named command line flag, if the flag is present.
+#[inline]
+#[must_use]
+pub fn flag(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}